@@ -2,17 +2,51 @@ use super::events::*;
 use super::frames::*;
 use super::grid::*;
 use super::models::*;
+use super::render::*;
 use super::utils::*;
 use dioxus::prelude::*;
 
 enum PointerState {
     Circle,
     Cube,
+    Paint,
+    Text,
+    Decal,
+    Polygon,
+    Rotate,
+    Voxel,
     None,
 }
 
+// Position de pose d'un nouveau cube : sur la face du dessus la plus haute
+// sous le curseur, sinon au niveau du sol
+fn placement_position(document: &Document, camera: &Camera, point: IsoCoord) -> GridCoord {
+    let elevation = document
+        .visible_shapes()
+        .iter()
+        .filter(|shape| {
+            shape
+                .faces()
+                .iter()
+                .any(|face| face.face_type == FaceType::Top && face.contains(point, camera))
+        })
+        .map(|shape| shape.bounds().max.z)
+        .reduce(f64::max)
+        .unwrap_or(0.0);
+
+    camera.grid_under(point, elevation)
+}
+
 #[component]
-pub fn Pointer(mouse: Signal<MousePos>, pan: Signal<Pan>, zoom: Signal<Zoom>) -> Element {
+pub fn Pointer(
+    mouse: Signal<MousePos>,
+    pan: Signal<Pan>,
+    zoom: Signal<Zoom>,
+    document: Signal<Document>,
+    camera: Camera,
+    outline: Signal<Vec<GridCoord>>,
+    selection: Signal<Vec<NodeId>>,
+) -> Element {
     let mouse_pos = *mouse.read();
     let (x_svg, y_svg) = screen_to_svg(
         mouse_pos.x,
@@ -24,21 +58,27 @@ pub fn Pointer(mouse: Signal<MousePos>, pan: Signal<Pan>, zoom: Signal<Zoom>) ->
     let pointer_state_signal = use_context::<Signal<PointerState>>();
     let pointer_state = pointer_state_signal.read();
 
+    // Identifiant propre au fantôme, gardé d'un rendu à l'autre
+    let preview_id = use_hook(NodeId::new);
+
     match *pointer_state {
         PointerState::Cube => rsx! {
             ShapePolygon {
-                shape: Shape::new(
-                    0,
-                    IsoCoord::new(x_svg, y_svg).into(),
-                    Dimensions::new(1, 1, 1),
-                    "#aa99aa".to_string()
+                preview: true,
+                camera,
+                light: document.read().light,
+                shape: Cuboid::new(
+                    preview_id,
+                    placement_position(&document.read(), &camera, IsoCoord::new(x_svg, y_svg)),
+                    Dimensions::new(1.0, 1.0, 1.0),
+                    Color::rgb(0xaa, 0x99, 0xaa)
                 ).with_style(ShapeStyle::new(
-                     "#aa99aa".to_string(),  // couleur de remplissage
-                     "black".to_string(),
+                     Color::rgb(0xaa, 0x99, 0xaa),  // couleur de remplissage
+                     Color::BLACK,
                      0.3,                    // sans bordure
                      0.4,                    // bordure transparente
                      0.6,                    // remplissage semi-transparent
-                 ))
+                 )).into()
             },
         },
         PointerState::Circle => rsx! {
@@ -51,7 +91,83 @@ pub fn Pointer(mouse: Signal<MousePos>, pan: Signal<Pan>, zoom: Signal<Zoom>) ->
                 stroke_width: "{1.5 / zoom.read().0}"
             },
         },
-        PointerState::None => rsx! {},
+        // Contour en cours, prolongé jusqu'au point de la grille sous le curseur
+        PointerState::Polygon => {
+            let cursor = camera.grid_under(IsoCoord::new(x_svg, y_svg), 0.0);
+            let points: Vec<IsoCoord> = outline
+                .read()
+                .iter()
+                .chain(std::iter::once(&cursor))
+                .map(|&p| camera.project_grid(p))
+                .collect();
+            let polyline = points
+                .iter()
+                .map(|p| format!("{},{}", p.x, p.y))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            rsx! {
+                polyline {
+                    points: "{polyline}",
+                    fill: "none",
+                    stroke: "#00aa66",
+                    stroke_width: "{1.5 / zoom.read().0}",
+                    pointer_events: "none",
+                }
+                for point in points {
+                    circle {
+                        cx: "{point.x}",
+                        cy: "{point.y}",
+                        r: "{3.0 / zoom.read().0}",
+                        fill: "#00aa66",
+                        pointer_events: "none",
+                    }
+                }
+            }
+        }
+        // Contour en pointillés de la boîte de chaque forme sélectionnée
+        PointerState::Rotate => {
+            let selection = selection.read();
+            let outlines: Vec<String> = document
+                .read()
+                .visible_shapes()
+                .iter()
+                .filter(|shape| selection.contains(&shape.id()))
+                .map(|shape| {
+                    let faces: Vec<Face> = ShapeVertices::from_bounds(shape.bounds())
+                        .faces()
+                        .into_iter()
+                        .filter(|face| face.is_visible(&camera))
+                        .collect();
+                    outline_polygon(&outline_edges(&faces))
+                        .iter()
+                        .map(|&p| {
+                            let point = camera.project(p);
+                            format!("{},{}", point.x, point.y)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect();
+
+            rsx! {
+                for points in outlines {
+                    polygon {
+                        points: "{points}",
+                        fill: "none",
+                        stroke: "#00aa66",
+                        stroke_width: "{1.5 / zoom.read().0}",
+                        stroke_dasharray: "{4.0 / zoom.read().0}",
+                        pointer_events: "none",
+                    }
+                }
+            }
+        }
+        PointerState::Paint
+        | PointerState::Text
+        | PointerState::Decal
+        | PointerState::Voxel
+        | PointerState::None => rsx! {},
     }
 }
 
@@ -71,29 +187,196 @@ pub fn Canvas() -> Element {
     let mut pointer_state = use_signal(|| PointerState::Circle);
     use_context_provider(|| pointer_state);
 
-    let shapes: Signal<Vec<Shape>> = use_signal(|| {
-        vec![
-            Shape::new(
-                3,
-                GridCoord { x: 20, y: 1 },
-                Dimensions::new(2, 2, 1),
-                "#FF6B6B".to_string(),
-            ),
-            Shape::new(
-                2,
-                GridCoord { x: 20, y: 0 },
-                Dimensions::new(1, 1, 3),
-                "#FF6B6B".to_string(),
-            ),
-            Shape::new(
-                1,
-                GridCoord { x: 19, y: 0 },
-                Dimensions::new(1, 1, 9),
-                "#AE6B6B".to_string(),
-            ),
-        ]
+    // Couleur appliquée aux faces en mode peinture
+    let mut paint = use_signal(|| Color::rgb(0x4E, 0xA8, 0xDE));
+
+    // Texte posé en mode texte
+    let mut label_text = use_signal(|| "Label".to_string());
+
+    // Contour du polygone en cours de tracé
+    let outline = use_signal(Vec::<GridCoord>::new);
+
+    // Dernière image importée, plaquée en mode image
+    let mut decal = use_signal(|| None::<Decal>);
+
+    // Modèle voxel qui reçoit les cubes peints
+    let voxel_model = use_signal(|| None::<NodeId>);
+
+    // Formes tournées ensemble par l'outil de rotation
+    let selection = use_signal(Vec::<NodeId>::new);
+
+    // Orientation de la vue, indépendante du document
+    let view = use_signal(View::default);
+
+    let mut document = use_signal(|| {
+        let mut document = Document::new();
+        document.add_shape(
+            Cuboid::new(
+                document.new_id(),
+                GridCoord::new(20.0, 1.0, 0.0),
+                Dimensions::new(2.0, 2.0, 1.0),
+                Color::rgb(0xFF, 0x6B, 0x6B),
+            )
+            .into(),
+        );
+
+        // Réservoir
+        document.add_shape(
+            Cylinder::new(
+                document.new_id(),
+                GridCoord::new(23.0, 0.0, 0.0),
+                1.0,
+                2.0,
+                Color::rgb(0x4E, 0xA8, 0xDE),
+            )
+            .into(),
+        );
+
+        // Boule posée sur un dôme
+        document.add_shape(
+            Sphere::hemisphere(
+                document.new_id(),
+                GridCoord::new(26.0, 0.0, 0.0),
+                1.5,
+                Color::rgb(0x9B, 0xC5, 0x3D),
+            )
+            .into(),
+        );
+        document.add_shape(
+            Sphere::new(
+                document.new_id(),
+                GridCoord::new(27.0, 1.0, 1.5),
+                0.5,
+                Color::rgb(0xF2, 0xC1, 0x4E),
+            )
+            .into(),
+        );
+
+        // Maison au toit à deux pans, rampe et pyramide
+        document.add_shape(
+            Cuboid::new(
+                document.new_id(),
+                GridCoord::new(30.0, 0.0, 0.0),
+                Dimensions::new(3.0, 2.0, 2.0),
+                Color::rgb(0xE8, 0xDD, 0xD0),
+            )
+            .into(),
+        );
+        document.add_shape(
+            Prism::new(
+                document.new_id(),
+                GridCoord::new(30.0, 0.0, 2.0),
+                Dimensions::new(3.0, 2.0, 1.0),
+                Direction::PlusX,
+                Color::rgb(0xC0, 0x4B, 0x3A),
+            )
+            .into(),
+        );
+        document.add_shape(
+            Wedge::new(
+                document.new_id(),
+                GridCoord::new(30.0, 3.0, 0.0),
+                Dimensions::new(3.0, 1.0, 1.0),
+                Direction::PlusX,
+                Color::rgb(0xAA, 0xAA, 0xAA),
+            )
+            .into(),
+        );
+        document.add_shape(
+            Pyramid::new(
+                document.new_id(),
+                GridCoord::new(34.0, 0.0, 0.0),
+                Dimensions::new(2.0, 2.0, 2.0),
+                Color::rgb(0xF2, 0xC1, 0x4E),
+            )
+            .into(),
+        );
+
+        // Bâtiment en L
+        document.add_shape(
+            Extrusion::new(
+                document.new_id(),
+                GridCoord::new(37.0, 0.0, 0.0),
+                vec![
+                    (0.0, 0.0),
+                    (3.0, 0.0),
+                    (3.0, 1.0),
+                    (1.0, 1.0),
+                    (1.0, 3.0),
+                    (0.0, 3.0),
+                ],
+                1.5,
+                Color::rgb(0x8E, 0x9A, 0xAF),
+            )
+            .into(),
+        );
+
+        // Escalier de quatre marches
+        document.add_shape(
+            Staircase::new(
+                document.new_id(),
+                GridCoord::new(41.0, 0.0, 0.0),
+                4,
+                Direction::PlusY,
+                Color::rgb(0xC8, 0xB0, 0x8A),
+            )
+            .with_width(1.5)
+            .into(),
+        );
+
+        // Petit modèle voxel : un champignon
+        let mut mushroom = VoxelModel::new(document.new_id(), GridCoord::new(44.0, 0.0, 0.0));
+        for (x, y) in [(1, 1), (1, 2), (2, 1), (2, 2)] {
+            mushroom
+                .voxels
+                .insert((x, y, 0), Color::rgb(0xF0, 0xE6, 0xD2));
+        }
+        for x in 0..4 {
+            for y in 0..4 {
+                let color = if (x + y) % 3 == 0 {
+                    Color::WHITE
+                } else {
+                    Color::rgb(0xD9, 0x3B, 0x3B)
+                };
+                mushroom.voxels.insert((x, y, 1), color);
+            }
+        }
+        document.add_shape(mushroom.into());
+
+        // Mur de briques
+        let mut wall = Cuboid::new(
+            document.new_id(),
+            GridCoord::new(0.0, 0.0, 0.0),
+            Dimensions::new(1.0, 1.0, 9.0),
+            Color::rgb(0xAE, 0x6B, 0x6B),
+        );
+        wall.style.fill = Some(Fill::pattern(
+            PatternKind::Bricks,
+            Color::rgb(0xE8, 0xDD, 0xD0),
+        ));
+
+        // Positions des formes relatives au dossier
+        let mut tower = Folder::new(document.new_id(), "Tower".to_string()).with_children(vec![
+            AnyShape::from(Cuboid::new(
+                document.new_id(),
+                GridCoord::new(1.0, 0.0, 0.0),
+                Dimensions::new(1.0, 1.0, 3.0),
+                Color::rgb(0xFF, 0x6B, 0x6B),
+            ))
+            .into(),
+            AnyShape::from(wall).into(),
+        ]);
+        tower.offset = GridCoord::new(19.0, 0.0, 0.0);
+        document.add_node_to(0, tower.into());
+
+        document
     });
 
+    let camera = {
+        let document = document.read();
+        Camera::new(document.projection, *view.read(), document.grid)
+    };
+
     rsx! {
         svg {
             width: "100%",
@@ -109,12 +392,31 @@ pub fn Canvas() -> Element {
             onmousedown: handle_mouse_down(is_panning, last_mouse, mouse),
             onmouseup: handle_mouse_up(is_panning),
             onwheel: handle_wheel(zoom, pan, mouse),
+            onclick: {
+                let mut paint_face = handle_paint_face(document, pan, zoom, camera, paint);
+                let mut add_label = handle_add_label(document, pan, zoom, camera, label_text);
+                let mut attach_decal = handle_attach_decal(document, pan, zoom, camera, decal);
+                let mut add_outline_point =
+                    handle_add_outline_point(document, pan, zoom, camera, outline);
+                let mut rotate_shape = handle_rotate_shape(document, pan, zoom, camera, selection);
+                let mut paint_voxel =
+                    handle_paint_voxel(document, pan, zoom, camera, paint, voxel_model);
+                move |event| match *pointer_state.read() {
+                    PointerState::Paint => paint_face(event),
+                    PointerState::Text => add_label(event),
+                    PointerState::Decal => attach_decal(event),
+                    PointerState::Polygon => add_outline_point(event),
+                    PointerState::Rotate => rotate_shape(event),
+                    PointerState::Voxel => paint_voxel(event),
+                    _ => {}
+                }
+            },
 
             g {
                 transform: "translate({pan.read().x}, {pan.read().y}) scale({zoom.read().0})",
-                IsometricGrid { pan, zoom, canvas_size }
-                ShapeList { shapes }
-                Pointer { mouse, pan, zoom }
+                IsometricGrid { pan, zoom, canvas_size, camera }
+                DocumentView { document, camera }
+                Pointer { mouse, pan, zoom, document, camera, outline, selection }
             }
         }
 
@@ -133,11 +435,135 @@ pub fn Canvas() -> Element {
                 "Cube"
             }
 
+            button {
+                onclick: move |_| *pointer_state.write() = PointerState::Polygon,
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Polygon"
+            }
+
+            button {
+                onclick: move |_| *pointer_state.write() = PointerState::Paint,
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Paint face"
+            }
+
+            input {
+                r#type: "color",
+                value: "{paint}",
+                oninput: move |event| {
+                    if let Ok(color) = event.value().parse() {
+                        paint.set(color);
+                    }
+                },
+            }
+
+            button {
+                onclick: move |_| *pointer_state.write() = PointerState::Voxel,
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Voxel"
+            }
+
+            button {
+                onclick: move |_| *pointer_state.write() = PointerState::Text,
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Text"
+            }
+
+            input {
+                r#type: "text",
+                value: "{label_text}",
+                oninput: move |event| label_text.set(event.value()),
+            }
+
+            // L'import d'une image passe directement en mode image
+            input {
+                r#type: "file",
+                accept: "image/*",
+                onchange: move |event| async move {
+                    let Some(file) = event.files().into_iter().next() else {
+                        return;
+                    };
+                    let Ok(bytes) = file.read_bytes().await else {
+                        return;
+                    };
+
+                    let mime = file
+                        .content_type()
+                        .filter(|mime| mime.starts_with("image/"))
+                        .unwrap_or_else(|| image_mime(&file.name()).to_string());
+                    decal.set(Some(Decal::from_bytes(&bytes, &mime)));
+                    *pointer_state.write() = PointerState::Decal;
+                },
+            }
+
+            button {
+                onclick: move |_| *pointer_state.write() = PointerState::Rotate,
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Rotate"
+            }
+
             button {
                 onclick: move |_| *pointer_state.write() = PointerState::None,
                 style: "padding: 8px 16px; cursor: pointer;",
                 "None"
             }
+
+            button {
+                onclick: move |_| document.write().projection = Projection::Isometric,
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Isometric"
+            }
+
+            button {
+                onclick: move |_| document.write().projection = Projection::Dimetric,
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Dimetric 2:1"
+            }
+
+            // Subdivisions de la grille : 1, 2 puis 4 par case
+            button {
+                onclick: move |_| {
+                    let mut document = document.write();
+                    let subdivisions = document.grid.subdivisions % 4 * 2;
+                    document.grid.subdivisions = subdivisions.max(1);
+                },
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Grid 1/{document.read().grid.subdivisions}"
+            }
+
+            // La lumière tourne d'un huitième de tour autour de la scène
+            button {
+                onclick: move |_| document.write().light.rotate(45.0),
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Light ⟳"
+            }
+
+            button {
+                onclick: move |_| {
+                    let mut document = document.write();
+                    document.light.cast_shadows = !document.light.cast_shadows;
+                },
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Shadows"
+            }
+
+            button {
+                onclick: handle_view_change(view, pan, zoom, canvas_size, camera, View::rotate_counter_clockwise),
+                style: "padding: 8px 16px; cursor: pointer;",
+                "⟲ Rotate"
+            }
+
+            button {
+                onclick: handle_view_change(view, pan, zoom, canvas_size, camera, View::rotate_clockwise),
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Rotate ⟳"
+            }
+
+            button {
+                onclick: handle_view_change(view, pan, zoom, canvas_size, camera, View::mirror),
+                style: "padding: 8px 16px; cursor: pointer;",
+                "Mirror"
+            }
         }
 
         // div {
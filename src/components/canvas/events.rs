@@ -1,3 +1,5 @@
+use super::frames::*;
+use super::models::*;
 use super::utils::*;
 use dioxus::prelude::*;
 use dioxus_elements::geometry::ElementPoint;
@@ -8,7 +10,7 @@ use dioxus_elements::input_data::MouseButton;
 pub fn handle_resize(mut canvas_size: Signal<CanvasSize>) -> impl FnMut(Event<ResizeData>) {
     move |data| {
         if let Ok(size) = data.get_border_box_size() {
-            let new_size = CanvasSize::from((size.width as f64, size.height as f64));
+            let new_size = CanvasSize::from((size.width, size.height));
             if *canvas_size.read() != new_size {
                 canvas_size.set(new_size);
             }
@@ -72,6 +74,301 @@ pub fn handle_wheel(
     }
 }
 
+// Change l'orientation de la vue en gardant au centre de l'écran
+// le même point du sol
+pub fn handle_view_change(
+    mut view: Signal<View>,
+    mut pan: Signal<Pan>,
+    zoom: Signal<Zoom>,
+    canvas_size: Signal<CanvasSize>,
+    camera: Camera,
+    change: fn(&mut View),
+) -> impl FnMut(Event<MouseData>) {
+    move |_| {
+        let size = *canvas_size.read();
+        let zoom = zoom.read().0;
+        let (center_x, center_y) = screen_to_svg(
+            size.width / 2.0,
+            size.height / 2.0,
+            (*pan.read()).into(),
+            zoom,
+        );
+
+        let ground = camera.unproject(IsoCoord::new(center_x, center_y), 0.0);
+        view.with_mut(change);
+        let moved = Camera::new(camera.projection, *view.read(), camera.grid).project(ground);
+
+        pan.with_mut(|p| {
+            p.x += (center_x - moved.x) * zoom;
+            p.y += (center_y - moved.y) * zoom;
+        });
+    }
+}
+
+// Peint la face sous le curseur ; avec Maj, la face reprend la couleur de la forme.
+// Sur un modèle voxel, c'est le cube sous le curseur qui change de couleur
+pub fn handle_paint_face(
+    mut document: Signal<Document>,
+    pan: Signal<Pan>,
+    zoom: Signal<Zoom>,
+    camera: Camera,
+    paint: Signal<Color>,
+) -> impl FnMut(Event<MouseData>) {
+    move |event: Event<MouseData>| {
+        let coords = event.data.coordinates().element();
+        let (x, y) = screen_to_svg(coords.x, coords.y, (*pan.read()).into(), zoom.read().0);
+        let Some((id, face)) = document.read().face_under(IsoCoord::new(x, y), &camera) else {
+            return;
+        };
+        let face_type = face.face_type;
+        let erase = event.data.modifiers().contains(Modifiers::SHIFT);
+
+        // Chaque cube a sa propre couleur, sans style de forme à retrouver
+        let target = world_voxels(&document.read(), id);
+        if let Some(target) = target {
+            let key = target.key_behind(&face);
+            if let Some(AnyShape::Voxels(voxels)) = document.write().shape_mut(id) {
+                if !erase && voxels.voxels.contains(key) {
+                    voxels.voxels.insert(key, *paint.read());
+                }
+            }
+            return;
+        }
+
+        // Une face dont le type se répète sur la forme ne se peint pas seule :
+        // toutes les autres changeraient avec elle
+        if let Some(shape) = document.write().shape_mut(id) {
+            if erase {
+                shape.style_mut().clear_paint(face_type);
+            } else if shape.has_own_style(face_type) {
+                shape.style_mut().face_mut(face_type).color = Some(*paint.read());
+            }
+        }
+    }
+}
+
+// Plaque l'image importée sur la face sous le curseur ; avec Maj, la face
+// perd son image
+pub fn handle_attach_decal(
+    mut document: Signal<Document>,
+    pan: Signal<Pan>,
+    zoom: Signal<Zoom>,
+    camera: Camera,
+    decal: Signal<Option<Decal>>,
+) -> impl FnMut(Event<MouseData>) {
+    move |event: Event<MouseData>| {
+        let coords = event.data.coordinates().element();
+        let (x, y) = screen_to_svg(coords.x, coords.y, (*pan.read()).into(), zoom.read().0);
+        let Some((id, face_type)) = document.read().face_at(IsoCoord::new(x, y), &camera) else {
+            return;
+        };
+
+        if event.data.modifiers().contains(Modifiers::SHIFT) {
+            document.write().attach_decal(id, face_type, None);
+        } else if let Some(decal) = decal.read().clone() {
+            document.write().attach_decal(id, face_type, Some(decal));
+        }
+    }
+}
+
+// Tourne d'un quart de tour la forme sous le curseur, ou toute la sélection
+// si la forme en fait partie, autour du centre de leur empreinte commune ;
+// avec Maj, dans le sens inverse des aiguilles d'une montre. Avec Ctrl, la
+// forme entre dans la sélection ou en sort ; un clic dans le vide la vide
+pub fn handle_rotate_shape(
+    mut document: Signal<Document>,
+    pan: Signal<Pan>,
+    zoom: Signal<Zoom>,
+    camera: Camera,
+    mut selection: Signal<Vec<NodeId>>,
+) -> impl FnMut(Event<MouseData>) {
+    move |event: Event<MouseData>| {
+        let coords = event.data.coordinates().element();
+        let (x, y) = screen_to_svg(coords.x, coords.y, (*pan.read()).into(), zoom.read().0);
+        let Some((id, _)) = document.read().face_at(IsoCoord::new(x, y), &camera) else {
+            selection.write().clear();
+            return;
+        };
+
+        let modifiers = event.data.modifiers();
+        if modifiers.contains(Modifiers::CONTROL) || modifiers.contains(Modifiers::META) {
+            let mut selection = selection.write();
+            match selection.iter().position(|&selected| selected == id) {
+                Some(index) => {
+                    selection.remove(index);
+                }
+                None => selection.push(id),
+            }
+            return;
+        }
+
+        let turn = if modifiers.contains(Modifiers::SHIFT) {
+            Turn::CounterClockwise
+        } else {
+            Turn::Clockwise
+        };
+
+        // Les formes supprimées depuis sortent de la sélection
+        selection.retain(|&selected| document.read().shape(selected).is_some());
+        let ids = if selection.read().contains(&id) {
+            selection.read().clone()
+        } else {
+            vec![id]
+        };
+        document.write().rotate(&ids, turn, None);
+    }
+}
+
+// Peint un cube d'une case : contre la face du cube sous le curseur, sur le
+// dessus de la forme sous le curseur ou sur le sol. Avec Maj, le cube sous
+// le curseur est effacé. Les cubes vont dans le modèle voxel actif, créé au
+// premier cube ; cliquer sur un autre modèle le rend actif
+pub fn handle_paint_voxel(
+    mut document: Signal<Document>,
+    pan: Signal<Pan>,
+    zoom: Signal<Zoom>,
+    camera: Camera,
+    paint: Signal<Color>,
+    mut model: Signal<Option<NodeId>>,
+) -> impl FnMut(Event<MouseData>) {
+    move |event: Event<MouseData>| {
+        let coords = event.data.coordinates().element();
+        let (x, y) = screen_to_svg(coords.x, coords.y, (*pan.read()).into(), zoom.read().0);
+        let point = IsoCoord::new(x, y);
+        let erase = event.data.modifiers().contains(Modifiers::SHIFT);
+        let under = document.read().face_under(point, &camera);
+
+        if let Some((id, face)) = &under {
+            let target = world_voxels(&document.read(), *id);
+            if let Some(target) = target {
+                let key = if erase {
+                    target.key_behind(face)
+                } else {
+                    target.key_in_front(face)
+                };
+                model.set(Some(*id));
+                if let Some(AnyShape::Voxels(voxels)) = document.write().shape_mut(*id) {
+                    if erase {
+                        voxels.voxels.remove(key);
+                    } else {
+                        voxels.voxels.insert(key, *paint.read());
+                    }
+                }
+                return;
+            }
+        }
+        if erase {
+            return;
+        }
+
+        let height = match &under {
+            Some((_, face)) if face.face_type == FaceType::Top => {
+                let bounds = face.bounds();
+                if bounds.max.z - bounds.min.z < 1e-9 {
+                    bounds.max.z
+                } else {
+                    return;
+                }
+            }
+            Some(_) => return,
+            None => 0.0,
+        };
+
+        let active = *model.read();
+        let id = match active {
+            Some(id) if world_voxels(&document.read(), id).is_some() => id,
+            _ => {
+                let id = document.read().new_id();
+                document
+                    .write()
+                    .add_shape(VoxelModel::new(id, GridCoord::new(0.0, 0.0, 0.0)).into());
+                id
+            }
+        };
+        model.set(Some(id));
+
+        let Some(target) = world_voxels(&document.read(), id) else {
+            return;
+        };
+        let key = target.key_at(camera.unproject(point, height) + CartCoord::new(0.0, 0.0, 0.5));
+        if let Some(AnyShape::Voxels(voxels)) = document.write().shape_mut(id) {
+            voxels.voxels.insert(key, *paint.read());
+        }
+    }
+}
+
+// Trace au sol le contour d'un polygone, point par point. Revenir au premier
+// point ferme le contour et crée la forme extrudée
+pub fn handle_add_outline_point(
+    mut document: Signal<Document>,
+    pan: Signal<Pan>,
+    zoom: Signal<Zoom>,
+    camera: Camera,
+    mut outline: Signal<Vec<GridCoord>>,
+) -> impl FnMut(Event<MouseData>) {
+    move |event: Event<MouseData>| {
+        let coords = event.data.coordinates().element();
+        let (x, y) = screen_to_svg(coords.x, coords.y, (*pan.read()).into(), zoom.read().0);
+        let point = camera.grid_under(IsoCoord::new(x, y), 0.0);
+
+        if outline.read().first() != Some(&point) {
+            if outline.read().last() != Some(&point) {
+                outline.write().push(point);
+            }
+            return;
+        }
+
+        if outline.read().len() >= 3 {
+            let id = document.read().new_id();
+            let shape =
+                Extrusion::from_outline(id, &outline.read(), 1.0, Color::rgb(0xAA, 0x99, 0xAA));
+            document.write().add_shape(shape.into());
+        }
+        outline.write().clear();
+    }
+}
+
+// Pose un texte : sur la face sous le curseur, qu'il suivra, sinon à plat
+// sur le sol
+pub fn handle_add_label(
+    mut document: Signal<Document>,
+    pan: Signal<Pan>,
+    zoom: Signal<Zoom>,
+    camera: Camera,
+    text: Signal<String>,
+) -> impl FnMut(Event<MouseData>) {
+    move |event: Event<MouseData>| {
+        let text = text.read().clone();
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let coords = event.data.coordinates().element();
+        let (x, y) = screen_to_svg(coords.x, coords.y, (*pan.read()).into(), zoom.read().0);
+        let point = IsoCoord::new(x, y);
+
+        let anchor = match document.read().face_at(point, &camera) {
+            // Pas de texte sur une face dont le type se répète
+            Some((id, face)) => match document
+                .read()
+                .shape(id)
+                .and_then(|shape| LabelAnchor::on_face(shape, face))
+            {
+                Some(anchor) => anchor,
+                None => return,
+            },
+            None => LabelAnchor::Plane {
+                plane: LabelPlane::Top,
+                position: camera.grid_under(point, 0.0),
+                direction: Direction::default(),
+            },
+        };
+
+        let id = document.read().new_id();
+        document.write().add_label(Label::new(id, text, anchor));
+    }
+}
+
 // ========= Sous-fonctions avec conditions ========= //
 
 fn should_start_panning(event: &Event<MouseData>) -> bool {
@@ -114,3 +411,12 @@ fn adjust_pan_for_zoom(
         p.y = pos.y - (pos.y - p.y) * ratio;
     });
 }
+
+// Modèle voxel en coordonnées du monde, décalage des dossiers compris
+fn world_voxels(document: &Document, id: NodeId) -> Option<VoxelModel> {
+    let offset = document.offset_of(id)?;
+    match document.shape(id)?.translated(offset) {
+        AnyShape::Voxels(model) => Some(model),
+        _ => None,
+    }
+}
@@ -1,26 +1,30 @@
+use super::grid::Grid;
+use serde::{Deserialize, Serialize};
 use std::f64::consts::PI;
-use super::grid::GRID_SIZE;
+use std::ops::{Add, Mul, Sub};
 
 // Structure pour les coordonnées 2D (isométriques)
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct IsoCoord {
     pub x: f64,
     pub y: f64,
 }
 
 // Structure pour les coordonnées 3D (cartésiennes)
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct CartCoord {
     pub x: f64,
     pub y: f64,
     pub z: f64,
 }
 
-// Structure pour les coordonnées Grille
-#[derive(Debug, Clone, Copy, PartialEq)]
+// Structure pour les coordonnées Grille, en cases (fractions de case
+// possibles avec les subdivisions)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct GridCoord {
-    pub x: i32,
-    pub y: i32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
 }
 
 impl IsoCoord {
@@ -33,71 +37,312 @@ impl CartCoord {
     pub fn new(x: f64, y: f64, z: f64) -> Self {
         CartCoord { x, y, z }
     }
+
+    pub fn dot(self, other: CartCoord) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    // Vecteur unitaire de même direction (le vecteur nul reste nul)
+    pub fn normalized(self) -> CartCoord {
+        let length = self.length();
+        if length > 0.0 {
+            self * (1.0 / length)
+        } else {
+            self
+        }
+    }
+
+    pub fn cross(self, other: CartCoord) -> CartCoord {
+        CartCoord {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+impl Add for CartCoord {
+    type Output = CartCoord;
+
+    fn add(self, other: CartCoord) -> CartCoord {
+        CartCoord::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for CartCoord {
+    type Output = CartCoord;
+
+    fn sub(self, other: CartCoord) -> CartCoord {
+        CartCoord::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for CartCoord {
+    type Output = CartCoord;
+
+    fn mul(self, factor: f64) -> CartCoord {
+        CartCoord::new(self.x * factor, self.y * factor, self.z * factor)
+    }
 }
 
 impl GridCoord {
-    pub fn new(x: i32, y: i32) -> Self {
-        GridCoord { x, y }
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        GridCoord { x, y, z }
     }
 }
 
-// Conversion coordonnées cartésiennes -> isométriques
-impl From<CartCoord> for IsoCoord {
-    fn from(cart: CartCoord) -> Self {
+// ============================================================================
+// Projection
+// ============================================================================
+
+// Projection axonométrique : les axes x et y font le même angle avec
+// l'horizontale de l'écran, l'axe z reste vertical
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Projection {
+    // Isométrie vraie : 30°
+    #[default]
+    Isometric,
+    // Dimétrie 2:1 du pixel art : atan(1/2) ≈ 26.565°
+    Dimetric,
+    // Angle libre en degrés, borné à ]0°, 90°[
+    Custom(f64),
+}
+
+impl Projection {
+    // (cos, sin) de l'angle des axes, exacts pour les préréglages
+    fn axes(&self) -> (f64, f64) {
+        match self {
+            Projection::Isometric => (3.0_f64.sqrt() / 2.0, 0.5),
+            Projection::Dimetric => (2.0 / 5.0_f64.sqrt(), 1.0 / 5.0_f64.sqrt()),
+            Projection::Custom(degrees) => {
+                let angle = degrees.clamp(1.0, 89.0) * PI / 180.0;
+                (angle.cos(), angle.sin())
+            }
+        }
+    }
+
+    pub fn angle_degrees(&self) -> f64 {
+        let (cos, sin) = self.axes();
+        sin.atan2(cos) * 180.0 / PI
+    }
+
+    // Conversion coordonnées cartésiennes -> isométriques
+    pub fn project(&self, cart: CartCoord) -> IsoCoord {
+        let (cos, sin) = self.axes();
+
         IsoCoord {
-            x: (cart.x - cart.y) * (PI / 6.0).cos(),
-            y: (cart.x + cart.y) * (PI / 6.0).sin() - cart.z,
+            x: (cart.x - cart.y) * cos,
+            y: (cart.x + cart.y) * sin - cart.z,
         }
     }
+
+    // Conversion isométrique -> cartésien, sur le plan horizontal d'élévation `z`
+    pub fn unproject(&self, iso: IsoCoord, z: f64) -> CartCoord {
+        let (cos, sin) = self.axes();
+        let iso_y = iso.y + z;
+
+        let x = (iso.x / cos + iso_y / sin) / 2.0;
+        let y = (iso_y / sin - iso.x / cos) / 2.0;
+
+        CartCoord { x, y, z }
+    }
+
+    // Direction vers l'observateur : tous les points alignés sur ce vecteur
+    // se projettent au même endroit à l'écran
+    pub fn view_direction(&self) -> CartCoord {
+        let (_, sin) = self.axes();
+        CartCoord::new(1.0, 1.0, 2.0 * sin)
+    }
 }
 
-// Conversion isométrique -> cartésien (approximation)
-impl From<IsoCoord> for CartCoord {
-    fn from(iso: IsoCoord) -> Self {
-        let cos_val = (PI / 6.0).cos();
-        let sin_val = (PI / 6.0).sin();
+// ============================================================================
+// Camera
+// ============================================================================
 
-        let x = (iso.x / cos_val + iso.y / sin_val) / 2.0;
-        let y = (iso.y / sin_val - iso.x / cos_val) / 2.0;
+// Orientation de la vue : quarts de tour autour de l'axe vertical, puis
+// miroir gauche/droite (échange des axes x et y)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct View {
+    pub quarter_turns: u8,
+    pub mirrored: bool,
+}
+
+impl View {
+    pub fn rotate_clockwise(&mut self) {
+        self.quarter_turns = (self.quarter_turns + 1) % 4;
+    }
 
-        CartCoord { x, y, z: 0.0 }
+    pub fn rotate_counter_clockwise(&mut self) {
+        self.quarter_turns = (self.quarter_turns + 3) % 4;
+    }
+
+    pub fn mirror(&mut self) {
+        self.mirrored = !self.mirrored;
+    }
+
+    // Repère du monde -> repère de la vue (s'applique aussi aux vecteurs)
+    pub fn apply(&self, cart: CartCoord) -> CartCoord {
+        let (mut x, mut y) = (cart.x, cart.y);
+        for _ in 0..self.quarter_turns % 4 {
+            (x, y) = (-y, x);
+        }
+        if self.mirrored {
+            (x, y) = (y, x);
+        }
+        CartCoord::new(x, y, cart.z)
+    }
+
+    // Repère de la vue -> repère du monde
+    pub fn invert(&self, cart: CartCoord) -> CartCoord {
+        let (mut x, mut y) = (cart.x, cart.y);
+        if self.mirrored {
+            (x, y) = (y, x);
+        }
+        for _ in 0..self.quarter_turns % 4 {
+            (x, y) = (y, -x);
+        }
+        CartCoord::new(x, y, cart.z)
     }
 }
 
-// Conversion gille -> cartésien
-impl From<GridCoord> for CartCoord {
-    fn from(grid: GridCoord) -> Self {
-        let x = grid.x as f64 * GRID_SIZE;
-        let y = grid.y as f64 * GRID_SIZE;
+// Projection et grille du document combinées à l'orientation de la vue :
+// toutes les conversions entre le monde (en cases) et l'écran passent par là
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Camera {
+    pub projection: Projection,
+    pub view: View,
+    pub grid: Grid,
+}
+
+impl Camera {
+    pub fn new(projection: Projection, view: View, grid: Grid) -> Self {
+        Self {
+            projection,
+            view,
+            grid,
+        }
+    }
+
+    pub fn project(&self, cart: CartCoord) -> IsoCoord {
+        self.projection
+            .project(self.view.apply(cart) * self.grid.size)
+    }
 
-        CartCoord { x, y, z: 0.0 }
+    // Point du monde sous un point de l'écran, à l'élévation `z`
+    pub fn unproject(&self, iso: IsoCoord, z: f64) -> CartCoord {
+        let size = self.grid.size;
+        self.view
+            .invert(self.projection.unproject(iso, z * size) * (1.0 / size))
+    }
+
+    // Point de la grille le plus proche sous un point de l'écran, à l'élévation `z`
+    pub fn grid_under(&self, iso: IsoCoord, z: f64) -> GridCoord {
+        let cart = self.unproject(iso, z);
+        self.grid.snap_coord(GridCoord::new(cart.x, cart.y, z))
+    }
+
+    pub fn project_grid(&self, grid: GridCoord) -> IsoCoord {
+        self.project(grid.into())
+    }
+
+    // Direction vers l'observateur, dans le repère du monde
+    pub fn view_direction(&self) -> CartCoord {
+        self.view.invert(self.projection.view_direction())
+    }
+}
+
+impl Add for GridCoord {
+    type Output = GridCoord;
+
+    fn add(self, other: GridCoord) -> GridCoord {
+        GridCoord::new(self.x + other.x, self.y + other.y, self.z + other.z)
     }
 }
 
-// Conversion grille -> isométrique (via CartCoord)
-impl From<GridCoord> for IsoCoord {
+// Conversion gille -> cartésien (le monde est mesuré en cases)
+impl From<GridCoord> for CartCoord {
     fn from(grid: GridCoord) -> Self {
-        let cart: CartCoord = grid.into();
-        cart.into()
+        CartCoord::new(grid.x, grid.y, grid.z)
     }
 }
 
-// Conversion cartésien -> grille (avec clamping)
+// Conversion cartésien -> grille, sans accrochage (voir `Grid::snap_coord`)
 impl From<CartCoord> for GridCoord {
     fn from(cart: CartCoord) -> Self {
-        let x = (cart.x / GRID_SIZE).round() as i32;
-        let y = (cart.y / GRID_SIZE).round() as i32;
-
-        GridCoord { x, y }
+        GridCoord::new(cart.x, cart.y, cart.z)
     }
 }
 
-// Conversion isométriques -> grille (via CartCoord)
-impl From<IsoCoord> for GridCoord {
-    fn from(iso: IsoCoord) -> Self {
-        let cart: CartCoord = iso.into();
-        cart.into()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECTIONS: [Projection; 5] = [
+        Projection::Isometric,
+        Projection::Dimetric,
+        Projection::Custom(45.0),
+        // Angles hors bornes, ramenés à ]0°, 90°[
+        Projection::Custom(0.0),
+        Projection::Custom(120.0),
+    ];
+
+    fn points() -> [CartCoord; 3] {
+        [
+            CartCoord::new(0.0, 0.0, 0.0),
+            CartCoord::new(3.0, -2.0, 1.5),
+            CartCoord::new(-4.25, 7.0, -2.0),
+        ]
+    }
+
+    fn assert_close(a: CartCoord, b: CartCoord) {
+        let gap = a - b;
+        assert!(gap.dot(gap) < 1e-18, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn unproject_inverts_project() {
+        for projection in PROJECTIONS {
+            for point in points() {
+                let iso = projection.project(point);
+                assert_close(projection.unproject(iso, point.z), point);
+            }
+        }
     }
-}
 
+    fn views() -> Vec<View> {
+        (0..4)
+            .flat_map(|quarter_turns| {
+                [false, true].map(|mirrored| View {
+                    quarter_turns,
+                    mirrored,
+                })
+            })
+            .collect()
+    }
 
+    #[test]
+    fn views_invert() {
+        for view in views() {
+            for point in points() {
+                assert_close(view.invert(view.apply(point)), point);
+            }
+        }
+    }
+
+    #[test]
+    fn camera_unproject_inverts_project_in_every_view() {
+        for projection in PROJECTIONS {
+            for view in views() {
+                let camera = Camera::new(projection, view, Grid::default());
+                for point in points() {
+                    let iso = camera.project(point);
+                    assert_close(camera.unproject(iso, point.z), point);
+                }
+            }
+        }
+    }
+}
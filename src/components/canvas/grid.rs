@@ -1,31 +1,149 @@
 use super::frames::*;
 use super::utils::*;
 use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
-pub const GRID_SIZE: f64 = 30.0;
+// ============================================================================
+// Grid
+// ============================================================================
+
+// Réglages de grille d'un document : taille d'une case à l'écran (au zoom 1)
+// et subdivisions par case pour poser des formes fines ou décalées
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "GridFile")]
+pub struct Grid {
+    pub size: f64,
+    pub subdivisions: u32,
+}
+
+// Grille telle qu'écrite dans le fichier, vérifiée avant d'être utilisée :
+// une taille nulle ferait diviser par zéro la caméra
+#[derive(Deserialize)]
+struct GridFile {
+    size: f64,
+    subdivisions: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridError {
+    InvalidSize(f64),
+    NoSubdivisions,
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::InvalidSize(size) => write!(f, "invalid grid size: {size}"),
+            GridError::NoSubdivisions => write!(f, "grid subdivisions must be at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl TryFrom<GridFile> for Grid {
+    type Error = GridError;
+
+    fn try_from(file: GridFile) -> Result<Self, GridError> {
+        if !file.size.is_finite() || file.size <= 0.0 {
+            return Err(GridError::InvalidSize(file.size));
+        }
+        if file.subdivisions == 0 {
+            return Err(GridError::NoSubdivisions);
+        }
+        Ok(Self {
+            size: file.size,
+            subdivisions: file.subdivisions,
+        })
+    }
+}
+
+impl Grid {
+    pub fn new(size: f64, subdivisions: u32) -> Self {
+        Self {
+            size: size.max(1.0),
+            subdivisions: subdivisions.max(1),
+        }
+    }
+
+    // Plus petit pas d'accrochage, en cases
+    pub fn step(&self) -> f64 {
+        1.0 / self.subdivisions.max(1) as f64
+    }
+
+    // Valeur arrondie à la subdivision la plus proche
+    pub fn snap(&self, value: f64) -> f64 {
+        let subdivisions = self.subdivisions.max(1) as f64;
+        (value * subdivisions).round() / subdivisions
+    }
+
+    pub fn snap_coord(&self, coord: GridCoord) -> GridCoord {
+        GridCoord::new(self.snap(coord.x), self.snap(coord.y), self.snap(coord.z))
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            size: 30.0,
+            subdivisions: 1,
+        }
+    }
+}
+
+// ============================================================================
+// Grid Lines
+// ============================================================================
+
+// Écart minimal à l'écran (en pixels) pour afficher les lignes de subdivision
+const MIN_SUBDIVISION_SPACING: f64 = 6.0;
+
+type Line = (f64, f64, f64, f64);
 
 #[derive(Clone, PartialEq)]
 struct GridLines {
-    vertical: Vec<(f64, f64, f64, f64)>,
-    horizontal: Vec<(f64, f64, f64, f64)>,
+    vertical: Vec<Line>,
+    horizontal: Vec<Line>,
+    // Lignes de subdivision, plus discrètes
+    minor: Vec<Line>,
 }
 
-fn calculate_grid_step(zoom: f64) -> i32 {
-    // Déterminer le pas d'affichage selon le zoom
-    if zoom >= 0.8 {
+fn calculate_grid_step(cell_size: f64) -> i32 {
+    // Déterminer le pas d'affichage selon la taille d'une case à l'écran
+    if cell_size >= 24.0 {
         1 // Afficher toutes les lignes
-    } else if zoom >= 0.5 {
+    } else if cell_size >= 15.0 {
         2 // Une ligne sur 2
-    } else if zoom >= 0.25 {
+    } else if cell_size >= 7.5 {
         4 // Une ligne sur 4
-    } else if zoom >= 0.125 {
+    } else if cell_size >= 3.75 {
         8 // Une ligne sur 8
     } else {
         16 // Une ligne sur 16
     }
 }
 
+// Positions (en cases) des lignes principales et des lignes de subdivision
+// entre `min` et `max`
+fn line_positions(min: f64, max: f64, step: i32, subdivisions: u32) -> (Vec<f64>, Vec<f64>) {
+    // Indices min/max alignés sur le pas
+    let first = (min.floor() as i32 - 5) / step * step;
+    let last = (max.ceil() as i32 + 5) / step * step;
+
+    let major = (first..=last)
+        .step_by(step as usize)
+        .map(|i| i as f64)
+        .collect();
+    let minor = (first..last)
+        .flat_map(|i| (1..subdivisions).map(move |k| i as f64 + k as f64 / subdivisions as f64))
+        .collect();
+
+    (major, minor)
+}
+
 fn calculate_visible_grid(
+    camera: Camera,
     pan_x: f64,
     pan_y: f64,
     zoom: f64,
@@ -50,10 +168,10 @@ fn calculate_visible_grid(
         ),
     ];
 
-    // Convertir tous les coins en coordonnées cartésiennes
+    // Convertir tous les coins en coordonnées cartésiennes (en cases)
     let cart_coords: Vec<CartCoord> = corners
         .iter()
-        .map(|(x, y)| IsoCoord { x: *x, y: *y }.into())
+        .map(|(x, y)| camera.unproject(IsoCoord { x: *x, y: *y }, 0.0))
         .collect();
 
     // Trouver les limites min/max sur tous les coins
@@ -74,56 +192,47 @@ fn calculate_visible_grid(
         .map(|c| c.y)
         .fold(f64::NEG_INFINITY, f64::max);
 
-    // Calculer le pas d'affichage selon le zoom
-    let step = calculate_grid_step(zoom);
-
-    // Trouver les indices de grille min/max
-    let grid_min_x = (cart_min_x / GRID_SIZE).floor() as i32 - 5;
-    let grid_max_x = (cart_max_x / GRID_SIZE).ceil() as i32 + 5;
-    let grid_min_y = (cart_min_y / GRID_SIZE).floor() as i32 - 5;
-    let grid_max_y = (cart_max_y / GRID_SIZE).ceil() as i32 + 5;
+    // Calculer le pas d'affichage selon la taille des cases à l'écran
+    let cell_size = camera.grid.size * zoom;
+    let step = calculate_grid_step(cell_size);
 
-    // Ajuster les indices pour être alignés sur le pas
-    let grid_min_x = (grid_min_x / step) * step;
-    let grid_max_x = (grid_max_x / step) * step;
-    let grid_min_y = (grid_min_y / step) * step;
-    let grid_max_y = (grid_max_y / step) * step;
-
-    // Extension pour que les lignes soient assez longues
-    let extension = 1000.0;
+    // Les subdivisions ne s'affichent que si toutes les lignes principales
+    // sont visibles et assez espacées
+    let subdivisions = camera.grid.subdivisions.max(1);
+    let subdivisions = if step == 1 && cell_size / subdivisions as f64 >= MIN_SUBDIVISION_SPACING {
+        subdivisions
+    } else {
+        1
+    };
 
-    // Générer les lignes verticales (avec le pas)
-    let vertical = (grid_min_x..=grid_max_x)
-        .step_by(step as usize)
-        .map(|i| {
-            let x = i as f64 * GRID_SIZE;
-            let y_min = cart_min_y - extension;
-            let y_max = cart_max_y + extension;
+    let (xs, minor_xs) = line_positions(cart_min_x, cart_max_x, step, subdivisions);
+    let (ys, minor_ys) = line_positions(cart_min_y, cart_max_y, step, subdivisions);
 
-            let start: IsoCoord = CartCoord { x: x, y: y_min, z: 0.0 }.into();
-            let end: IsoCoord = CartCoord { x: x, y: y_max, z: 0.0 }.into();
+    // Extension pour que les lignes soient assez longues (en cases)
+    let extension = 1000.0 / camera.grid.size;
 
-            (start.x, start.y, end.x, end.y)
-        })
-        .collect();
+    // Ligne à x constant
+    let along_y = |x: f64| {
+        let start = camera.project(CartCoord::new(x, cart_min_y - extension, 0.0));
+        let end = camera.project(CartCoord::new(x, cart_max_y + extension, 0.0));
+        (start.x, start.y, end.x, end.y)
+    };
 
-    // Générer les lignes horizontales (avec le pas)
-    let horizontal = (grid_min_y..=grid_max_y)
-        .step_by(step as usize)
-        .map(|i| {
-            let y = i as f64 * GRID_SIZE;
-            let x_min = cart_min_x - extension;
-            let x_max = cart_max_x + extension;
-
-            let start: IsoCoord = CartCoord { x: x_min, y, z: 0.0 }.into();
-            let end: IsoCoord = CartCoord { x: x_max, y, z: 0.0 }.into();
-            (start.x, start.y, end.x, end.y)
-        })
-        .collect();
+    // Ligne à y constant
+    let along_x = |y: f64| {
+        let start = camera.project(CartCoord::new(cart_min_x - extension, y, 0.0));
+        let end = camera.project(CartCoord::new(cart_max_x + extension, y, 0.0));
+        (start.x, start.y, end.x, end.y)
+    };
 
     GridLines {
-        vertical,
-        horizontal,
+        vertical: xs.into_iter().map(along_y).collect(),
+        horizontal: ys.into_iter().map(along_x).collect(),
+        minor: minor_xs
+            .into_iter()
+            .map(along_y)
+            .chain(minor_ys.into_iter().map(along_x))
+            .collect(),
     }
 }
 
@@ -132,19 +241,38 @@ pub fn IsometricGrid(
     pan: Signal<Pan>,
     zoom: Signal<Zoom>,
     canvas_size: Signal<CanvasSize>,
+    camera: Camera,
 ) -> Element {
-    // Calculer les lignes de grille visibles
-    let grid_lines = use_memo(move || {
+    // Calculer les lignes de grille visibles (la caméra est une prop : il faut
+    // la rendre réactive pour que le memo suive ses changements)
+    let grid_lines = use_memo(use_reactive!(|camera| {
         calculate_visible_grid(
+            camera,
             pan.read().x,
             pan.read().y,
             zoom.read().0,
             canvas_size.read().width,
             canvas_size.read().height,
         )
-    });
+    }));
 
     rsx! {
+        // Lignes de subdivision
+        g {
+            stroke: "#dddddd",
+            stroke_width: "{0.5 / zoom.read().0}",
+
+            for (idx, (x1, y1, x2, y2)) in grid_lines().minor.iter().enumerate() {
+                line {
+                    key: "m-{idx}",
+                    x1: "{x1}",
+                    y1: "{y1}",
+                    x2: "{x2}",
+                    y2: "{y2}",
+                }
+            }
+        }
+
         g {
             stroke: "#b2b2b2",
             stroke_width: "{0.5 / zoom.read().0}",
@@ -173,3 +301,27 @@ pub fn IsometricGrid(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_with_null_size_is_rejected() {
+        let json = r#"{"size": 0.0, "subdivisions": 2}"#;
+        assert!(serde_json::from_str::<Grid>(json).is_err());
+    }
+
+    #[test]
+    fn grid_without_subdivisions_is_rejected() {
+        let json = r#"{"size": 30.0, "subdivisions": 0}"#;
+        assert!(serde_json::from_str::<Grid>(json).is_err());
+    }
+
+    #[test]
+    fn grid_round_trips() {
+        let grid = Grid::new(24.0, 4);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<Grid>(&json).unwrap(), grid);
+    }
+}
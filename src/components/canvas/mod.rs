@@ -1,8 +1,10 @@
+#[allow(clippy::module_inception)]
 pub mod canvas;
 pub mod events;
 pub mod frames;
 pub mod grid;
 pub mod models;
+pub mod render;
 pub mod utils;
 
 pub use canvas::Canvas;
@@ -0,0 +1,285 @@
+use super::super::frames::CartCoord;
+use super::shape::Face;
+
+// Tolérance de classification des sommets par rapport à un plan
+const EPSILON: f64 = 1e-6;
+
+// ============================================================================
+// Fragments
+// ============================================================================
+
+// Morceau de face à dessiner. Une face coupée par le BSP donne plusieurs
+// fragments ; seules les arêtes d'origine doivent recevoir le contour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fragment {
+    // Indice de la forme dans la liste d'origine
+    pub shape: usize,
+    pub face: Face,
+    // edges[i] : l'arête entre les sommets i et i + 1 appartient à la face d'origine
+    pub edges: Vec<bool>,
+}
+
+impl Fragment {
+    pub fn new(shape: usize, face: Face) -> Self {
+        let edges = vec![true; face.vertices.len()];
+        Self { shape, face, edges }
+    }
+
+    fn with_vertices(&self, vertices: Vec<CartCoord>, edges: Vec<bool>) -> Self {
+        Self {
+            shape: self.shape,
+            face: Face::new(self.face.face_type, vertices),
+            edges,
+        }
+    }
+}
+
+// ============================================================================
+// Plane
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+struct Plane {
+    normal: CartCoord,
+    offset: f64,
+}
+
+enum Split {
+    Coplanar,
+    Front,
+    Back,
+    Spanning(Fragment, Fragment),
+}
+
+impl Plane {
+    fn from_face(face: &Face) -> Self {
+        let normal = face.normal();
+        let length = normal.dot(normal).sqrt();
+        let normal = normal * (1.0 / length);
+
+        Self {
+            normal,
+            offset: normal.dot(face.vertices[0]),
+        }
+    }
+
+    fn distance(&self, point: CartCoord) -> f64 {
+        self.normal.dot(point) - self.offset
+    }
+
+    fn split(&self, fragment: &Fragment) -> Split {
+        let vertices = &fragment.face.vertices;
+        let distances: Vec<f64> = vertices.iter().map(|&v| self.distance(v)).collect();
+
+        let has_front = distances.iter().any(|&d| d > EPSILON);
+        let has_back = distances.iter().any(|&d| d < -EPSILON);
+
+        match (has_front, has_back) {
+            (false, false) => return Split::Coplanar,
+            (true, false) => return Split::Front,
+            (false, true) => return Split::Back,
+            (true, true) => {}
+        }
+
+        let mut front = (Vec::new(), Vec::new());
+        let mut back = (Vec::new(), Vec::new());
+        let count = vertices.len();
+
+        for i in 0..count {
+            let j = (i + 1) % count;
+            let (di, dj) = (distances[i], distances[j]);
+            let edge = fragment.edges[i];
+
+            // L'arête sortante d'un sommet reste une arête d'origine, sauf si
+            // le sommet suivant du même côté est le point d'entrée de la coupe
+            if di >= -EPSILON {
+                front.0.push(vertices[i]);
+                front.1.push(edge && !(di <= EPSILON && dj < -EPSILON));
+            }
+            if di <= EPSILON {
+                back.0.push(vertices[i]);
+                back.1.push(edge && !(di >= -EPSILON && dj > EPSILON));
+            }
+
+            if (di > EPSILON && dj < -EPSILON) || (di < -EPSILON && dj > EPSILON) {
+                let t = di / (di - dj);
+                let point = vertices[i] + (vertices[j] - vertices[i]) * t;
+                let leaving_front = di > EPSILON;
+
+                front.0.push(point);
+                front.1.push(edge && !leaving_front);
+                back.0.push(point);
+                back.1.push(edge && leaving_front);
+            }
+        }
+
+        Split::Spanning(
+            fragment.with_vertices(front.0, front.1),
+            fragment.with_vertices(back.0, back.1),
+        )
+    }
+}
+
+// ============================================================================
+// BSP Tree
+// ============================================================================
+
+struct BspNode {
+    plane: Plane,
+    fragments: Vec<Fragment>,
+    front: Option<Box<BspNode>>,
+    back: Option<Box<BspNode>>,
+}
+
+impl BspNode {
+    fn build(mut fragments: Vec<Fragment>) -> Option<Box<BspNode>> {
+        if fragments.is_empty() {
+            return None;
+        }
+
+        let splitter = fragments.remove(0);
+        let plane = Plane::from_face(&splitter.face);
+
+        let mut coplanar = vec![splitter];
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+
+        for fragment in fragments {
+            match plane.split(&fragment) {
+                Split::Coplanar => coplanar.push(fragment),
+                Split::Front => front.push(fragment),
+                Split::Back => back.push(fragment),
+                Split::Spanning(front_part, back_part) => {
+                    front.push(front_part);
+                    back.push(back_part);
+                }
+            }
+        }
+
+        Some(Box::new(BspNode {
+            plane,
+            fragments: coplanar,
+            front: BspNode::build(front),
+            back: BspNode::build(back),
+        }))
+    }
+
+    fn back_to_front(self, view: CartCoord, out: &mut Vec<Fragment>) {
+        // Le côté vers lequel pointe la normale fait face à l'observateur :
+        // l'autre côté est dessiné en premier
+        let (far, near) = if self.plane.normal.dot(view) >= 0.0 {
+            (self.back, self.front)
+        } else {
+            (self.front, self.back)
+        };
+
+        if let Some(node) = far {
+            node.back_to_front(view, out);
+        }
+        out.extend(self.fragments);
+        if let Some(node) = near {
+            node.back_to_front(view, out);
+        }
+    }
+}
+
+// Découpe les faces jusqu'à obtenir un ordre de dessin exact, y compris
+// quand les formes se chevauchent en cycle ou s'interpénètrent.
+// `view` pointe vers l'observateur.
+pub fn bsp_order(fragments: Vec<Fragment>, view: CartCoord) -> Vec<Fragment> {
+    let fragments: Vec<Fragment> = fragments
+        .into_iter()
+        .filter(|fragment| {
+            let normal = fragment.face.normal();
+            fragment.face.vertices.len() >= 3 && normal.dot(normal) > EPSILON
+        })
+        .collect();
+
+    let mut out = Vec::with_capacity(fragments.len());
+    if let Some(root) = BspNode::build(fragments) {
+        root.back_to_front(view, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::frames::Camera;
+    use super::super::depth::{polygons_overlap, render_order, DepthMode, RenderOrder};
+    use super::super::fixtures::cuboid;
+    use super::super::shape::Shape;
+    use super::*;
+
+    fn on_segment(point: CartCoord, (a, b): (CartCoord, CartCoord)) -> bool {
+        let t = (point - a).dot(b - a) / (b - a).dot(b - a);
+        let gap = a + (b - a) * t - point;
+        (-EPSILON..=1.0 + EPSILON).contains(&t) && gap.dot(gap) < EPSILON * EPSILON
+    }
+
+    fn edges(face: &Face) -> Vec<(CartCoord, CartCoord)> {
+        let count = face.vertices.len();
+        (0..count)
+            .map(|i| (face.vertices[i], face.vertices[(i + 1) % count]))
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_bars_are_split_back_to_front() {
+        let camera = Camera::default();
+        // Trois barres en tourniquet : aucun ordre des formes entières n'existe
+        let bars = [
+            cuboid((0.0, 0.0, 1.0), (3.0, 1.0, 1.0)),
+            cuboid((0.0, 1.0, 0.0), (1.0, 1.0, 3.0)),
+            cuboid((1.0, 0.0, 0.0), (1.0, 3.0, 1.0)),
+        ];
+
+        let RenderOrder::Fragments(fragments) = render_order(&bars, DepthMode::Auto, &camera)
+        else {
+            panic!("le cycle doit passer par le découpage des faces");
+        };
+
+        let view = camera.view_direction();
+        // Un fragment dessiné après un autre qui le recouvre à l'écran n'est
+        // jamais derrière le plan de ce dernier
+        for (i, back) in fragments.iter().enumerate() {
+            let plane = Plane::from_face(&back.face);
+            let screen = back.face.iso_vertices(&camera);
+            for front in &fragments[i + 1..] {
+                if !polygons_overlap(&screen, &front.face.iso_vertices(&camera)) {
+                    continue;
+                }
+                let side = if plane.normal.dot(view) >= 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                assert!(
+                    front
+                        .face
+                        .vertices
+                        .iter()
+                        .any(|&v| side * plane.distance(v) > EPSILON),
+                    "{front:?} dessiné devant {back:?}"
+                );
+            }
+        }
+
+        // Seules les arêtes des faces d'origine gardent le contour
+        let mut cuts = 0;
+        for fragment in &fragments {
+            let original = bars[fragment.shape]
+                .faces()
+                .into_iter()
+                .find(|face| face.face_type == fragment.face.face_type)
+                .unwrap();
+            for (&(a, b), &kept) in edges(&fragment.face).iter().zip(&fragment.edges) {
+                let on_boundary = edges(&original)
+                    .into_iter()
+                    .any(|edge| on_segment(a, edge) && on_segment(b, edge));
+                assert_eq!(kept, on_boundary, "arête {a:?} -> {b:?}");
+                cuts += usize::from(!kept);
+            }
+        }
+        assert!(cuts > 0, "au moins une face doit être coupée");
+    }
+}
@@ -0,0 +1,690 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// ============================================================================
+// Color
+// ============================================================================
+
+// Couleur sRGB avec transparence, composantes entre 0 et 1. Elle est écrite
+// en hexadécimal CSS dans les fichiers projet et dans le SVG
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+// Teinte en degrés, saturation et luminosité entre 0 et 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+}
+
+// Espace perceptuel OKLCH : luminosité entre 0 et 1, chroma (environ 0 à
+// 0.4) et teinte en degrés
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f64,
+    pub c: f64,
+    pub h: f64,
+}
+
+impl Color {
+    pub const BLACK: Color = Color {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    pub const WHITE: Color = Color {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+        a: 1.0,
+    };
+
+    pub const TRANSPARENT: Color = Color {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 0.0,
+    };
+
+    pub fn new(r: f64, g: f64, b: f64, a: f64) -> Self {
+        Self {
+            r: r.clamp(0.0, 1.0),
+            g: g.clamp(0.0, 1.0),
+            b: b.clamp(0.0, 1.0),
+            a: a.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 1.0)
+    }
+
+    pub fn rgba(r: u8, g: u8, b: u8, a: f64) -> Self {
+        Self::new(r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0, a)
+    }
+
+    pub fn with_alpha(self, a: f64) -> Self {
+        Self::new(self.r, self.g, self.b, a)
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.a >= 1.0
+    }
+
+    // Composantes sur 8 bits, arrondies
+    pub fn to_rgb8(self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a].map(|c| (c * 255.0).round() as u8)
+    }
+
+    // #rrggbb, ou #rrggbbaa si la couleur est transparente
+    pub fn to_hex(self) -> String {
+        let [r, g, b, a] = self.to_rgb8();
+        if self.is_opaque() {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // HSL
+    // ------------------------------------------------------------------------
+
+    pub fn from_hsl(hsl: Hsl) -> Self {
+        let s = hsl.s.clamp(0.0, 1.0);
+        let l = hsl.l.clamp(0.0, 1.0);
+        let a = s * l.min(1.0 - l);
+
+        let channel = |n: f64| {
+            let k = (n + hsl.h.rem_euclid(360.0) / 30.0) % 12.0;
+            l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+
+        Self::new(channel(0.0), channel(8.0), channel(4.0), 1.0)
+    }
+
+    // ------------------------------------------------------------------------
+    // OKLCH
+    // ------------------------------------------------------------------------
+
+    // Les couleurs hors du gamut sRGB sont ramenées composante par composante
+    pub fn from_oklch(oklch: Oklch) -> Self {
+        let hue = oklch.h.to_radians();
+        let (l, a, b) = (oklch.l, oklch.c * hue.cos(), oklch.c * hue.sin());
+
+        let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+        let r = 4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_;
+        let g = -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_;
+        let b = -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_;
+
+        Self::new(to_gamma(r), to_gamma(g), to_gamma(b), 1.0)
+    }
+
+    pub fn to_oklch(self) -> Oklch {
+        let (r, g, b) = (to_linear(self.r), to_linear(self.g), to_linear(self.b));
+
+        let l_ = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m_ = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s_ = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        let l = 0.2104542553 * l_ + 0.7936177850 * m_ - 0.0040720468 * s_;
+        let a = 1.9779984951 * l_ - 2.4285922050 * m_ + 0.4505937099 * s_;
+        let b = 0.0259040371 * l_ + 0.7827717662 * m_ - 0.8086757660 * s_;
+
+        let c = (a * a + b * b).sqrt();
+        // Teinte indéfinie pour les gris
+        let h = if c < 1e-6 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        };
+
+        Oklch { l, c, h }
+    }
+}
+
+// Transfert sRGB -> linéaire
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// Transfert linéaire -> sRGB
+fn to_gamma(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.max(0.0).powf(1.0 / 2.4) - 0.055
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        color.to_hex()
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = ColorError;
+
+    fn try_from(text: String) -> Result<Self, ColorError> {
+        text.parse()
+    }
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+    Empty,
+    InvalidHex(String),
+    UnknownName(String),
+    UnknownFunction(String),
+    InvalidArguments(String),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorError::Empty => write!(f, "empty color"),
+            ColorError::InvalidHex(text) => write!(
+                f,
+                "invalid hex color `{text}` (expected #rgb, #rgba, #rrggbb or #rrggbbaa)"
+            ),
+            ColorError::UnknownName(name) => write!(f, "unknown color name `{name}`"),
+            ColorError::UnknownFunction(name) => write!(
+                f,
+                "unknown color function `{name}()` (expected rgb, rgba, hsl, hsla or oklch)"
+            ),
+            ColorError::InvalidArguments(text) => write!(f, "invalid color arguments in `{text}`"),
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+// Syntaxes CSS acceptées : #rgb, #rgba, #rrggbb, #rrggbbaa, noms de couleur,
+// rgb()/rgba(), hsl()/hsla() et oklch(), avec virgules ou espaces et
+// transparence après `/`
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(input: &str) -> Result<Self, ColorError> {
+        let input = input.trim();
+        let text = input.to_ascii_lowercase();
+
+        if text.is_empty() {
+            return Err(ColorError::Empty);
+        }
+
+        if let Some(hex) = text.strip_prefix('#') {
+            return parse_hex(hex).ok_or_else(|| ColorError::InvalidHex(input.to_string()));
+        }
+
+        if let Some((name, rest)) = text.split_once('(') {
+            let arguments = rest
+                .strip_suffix(')')
+                .ok_or_else(|| ColorError::InvalidArguments(input.to_string()))?;
+
+            return match name.trim() {
+                "rgb" | "rgba" => parse_rgb(arguments),
+                "hsl" | "hsla" => parse_hsl(arguments),
+                "oklch" => parse_oklch(arguments),
+                name => return Err(ColorError::UnknownFunction(name.to_string())),
+            }
+            .ok_or_else(|| ColorError::InvalidArguments(input.to_string()));
+        }
+
+        if text == "transparent" {
+            return Ok(Color::TRANSPARENT);
+        }
+
+        NAMED_COLORS
+            .iter()
+            .find(|(name, _)| *name == text)
+            .map(|&(_, hex)| Color::rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8))
+            .ok_or_else(|| ColorError::UnknownName(input.to_string()))
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    // Formes courtes : chaque chiffre est doublé
+    let digits: Vec<u8> = match hex.len() {
+        3 | 4 => hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| (d * 17) as u8))
+            .collect::<Option<_>>()?,
+        6 | 8 => (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+
+    let alpha = digits.get(3).map_or(1.0, |&a| a as f64 / 255.0);
+    Some(Color::rgba(digits[0], digits[1], digits[2], alpha))
+}
+
+// Découpe les arguments en trois composantes et une transparence
+// optionnelle, en syntaxe moderne (`1 2 3 / 0.5`) ou ancienne (`1, 2, 3, 0.5`)
+fn split_arguments(arguments: &str) -> Option<([&str; 3], Option<&str>)> {
+    let (main, slash_alpha) = match arguments.split_once('/') {
+        Some((main, alpha)) => (main, Some(alpha.trim())),
+        None => (arguments, None),
+    };
+
+    let mut values: Vec<&str> = if main.contains(',') {
+        main.split(',').map(str::trim).collect()
+    } else {
+        main.split_whitespace().collect()
+    };
+
+    let alpha = match (slash_alpha, values.len()) {
+        (Some(alpha), 3) => Some(alpha),
+        (None, 4) => values.pop(),
+        (None, 3) => None,
+        _ => return None,
+    };
+
+    if values
+        .iter()
+        .chain(alpha.iter())
+        .any(|value| value.is_empty())
+    {
+        return None;
+    }
+
+    Some(([values[0], values[1], values[2]], alpha))
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    // `none` : composante absente, traitée comme zéro
+    if text == "none" {
+        return Some(0.0);
+    }
+    text.parse::<f64>().ok().filter(|value| value.is_finite())
+}
+
+// Pourcentage entre 0 % et 100 %, ramené entre 0 et 1
+fn parse_percentage(text: &str) -> Option<f64> {
+    parse_number(text.strip_suffix('%')?)
+        .filter(|value| (0.0..=100.0).contains(value))
+        .map(|value| value / 100.0)
+}
+
+// Nombre brut multiplié par `scale`, ou pourcentage de `percent_of`
+fn parse_value(text: &str, scale: f64, percent_of: f64) -> Option<f64> {
+    match text.strip_suffix('%') {
+        Some(_) => parse_percentage(text).map(|value| value * percent_of),
+        None => parse_number(text).map(|value| value * scale),
+    }
+}
+
+// Angle en degrés, avec ou sans unité
+fn parse_hue(text: &str) -> Option<f64> {
+    let units = [
+        ("deg", 1.0),
+        ("grad", 0.9),
+        ("rad", 180.0 / std::f64::consts::PI),
+        ("turn", 360.0),
+    ];
+
+    units
+        .iter()
+        .find_map(|(unit, factor)| {
+            text.strip_suffix(unit)
+                .and_then(parse_number)
+                .map(|value| value * factor)
+        })
+        .or_else(|| parse_number(text))
+}
+
+fn parse_alpha(alpha: Option<&str>) -> Option<f64> {
+    alpha.map_or(Some(1.0), |text| parse_value(text, 1.0, 1.0))
+}
+
+fn parse_rgb(arguments: &str) -> Option<Color> {
+    let ([r, g, b], alpha) = split_arguments(arguments)?;
+    let channel = |text| parse_value(text, 1.0 / 255.0, 1.0);
+
+    Some(Color::new(
+        channel(r)?,
+        channel(g)?,
+        channel(b)?,
+        parse_alpha(alpha)?,
+    ))
+}
+
+fn parse_hsl(arguments: &str) -> Option<Color> {
+    let ([h, s, l], alpha) = split_arguments(arguments)?;
+    let hsl = Hsl {
+        h: parse_hue(h)?,
+        s: parse_value(s, 0.01, 1.0)?,
+        l: parse_value(l, 0.01, 1.0)?,
+    };
+
+    Some(Color::from_hsl(hsl).with_alpha(parse_alpha(alpha)?))
+}
+
+fn parse_oklch(arguments: &str) -> Option<Color> {
+    let ([l, c, h], alpha) = split_arguments(arguments)?;
+    // En CSS, 100% de chroma vaut 0.4
+    let oklch = Oklch {
+        l: parse_value(l, 1.0, 1.0)?,
+        c: parse_value(c, 1.0, 0.4)?.max(0.0),
+        h: parse_hue(h)?,
+    };
+
+    Some(Color::from_oklch(oklch).with_alpha(parse_alpha(alpha)?))
+}
+
+// Couleurs nommées de CSS Color Level 4
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Color {
+        text.parse()
+            .unwrap_or_else(|error| panic!("`{text}` : {error}"))
+    }
+
+    fn assert_close(a: Color, b: Color) {
+        let [ar, ag, ab, aa] = [a.r, a.g, a.b, a.a];
+        let [br, bg, bb, ba] = [b.r, b.g, b.b, b.a];
+        let close = [(ar, br), (ag, bg), (ab, bb), (aa, ba)]
+            .iter()
+            .all(|(x, y)| (x - y).abs() < 1e-6);
+        assert!(close, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(parse("#f80"), Color::rgb(255, 136, 0));
+        assert_eq!(parse("#F808"), Color::rgba(255, 136, 0, 136.0 / 255.0));
+        assert_eq!(parse("#ff8800"), Color::rgb(255, 136, 0));
+        assert_eq!(
+            parse("  #FF880080 "),
+            Color::rgba(255, 136, 0, 128.0 / 255.0)
+        );
+        assert_eq!(Color::rgb(255, 136, 0).to_hex(), "#ff8800");
+        assert_eq!(Color::rgba(255, 136, 0, 0.5).to_hex(), "#ff880080");
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(parse("rebeccapurple"), Color::rgb(0x66, 0x33, 0x99));
+        assert_eq!(parse("White"), Color::WHITE);
+        assert_eq!(parse("transparent"), Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn rgb_function() {
+        let orange = Color::rgb(255, 136, 0);
+        assert_eq!(parse("rgb(255, 136, 0)"), orange);
+        assert_eq!(parse("rgb(255 136 0)"), orange);
+        assert_eq!(parse("rgba(255, 136, 0, 0.5)"), orange.with_alpha(0.5));
+        assert_eq!(parse("rgb(255 136 0 / 50%)"), orange.with_alpha(0.5));
+        assert_close(parse("rgb(100% 0% 50%)"), Color::new(1.0, 0.0, 0.5, 1.0));
+    }
+
+    #[test]
+    fn hsl_function() {
+        assert_close(parse("hsl(120, 100%, 50%)"), Color::rgb(0, 255, 0));
+        assert_close(parse("hsl(0.5turn 100% 50%)"), Color::rgb(0, 255, 255));
+        assert_close(
+            parse("hsla(240deg, 100%, 25%, 0.25)"),
+            Color::new(0.0, 0.0, 0.5, 0.25),
+        );
+        assert_close(parse("hsl(0 0% 100%)"), Color::WHITE);
+    }
+
+    #[test]
+    fn oklch_function() {
+        assert_close(parse("oklch(1 0 0)"), Color::WHITE);
+        assert_close(parse("oklch(0% 0 0)"), Color::BLACK);
+        // Rouge sRGB, à l'arrondi près des valeurs CSS
+        assert_eq!(parse("oklch(62.8% 0.2577 29.23)").to_hex(), "#ff0000");
+        assert_eq!(parse("oklch(0.628 0.2577 29.23 / 0.5)").a, 0.5);
+    }
+
+    #[test]
+    fn malformed_colors_are_rejected() {
+        let error = |text: &str| text.parse::<Color>().unwrap_err();
+
+        assert_eq!(error("  "), ColorError::Empty);
+        for text in ["#ff", "#fffff", "#fffffffff", "#ggg"] {
+            assert_eq!(error(text), ColorError::InvalidHex(text.to_string()));
+        }
+        for text in [
+            "rgb(150%, 0%, 0%)",
+            "rgb(0 0 0 / 120%)",
+            "hsl(0, -10%, 50%)",
+            "hsl(0 50% 101%)",
+            "rgb(1, 2, 3",
+            "rgb(1, 2)",
+            "rgb(1, 2, 3, 4, 5)",
+            "rgb(a, b, c)",
+        ] {
+            assert_eq!(error(text), ColorError::InvalidArguments(text.to_string()));
+        }
+        assert_eq!(
+            error("rgb 1, 2, 3"),
+            ColorError::UnknownName("rgb 1, 2, 3".to_string())
+        );
+        assert_eq!(
+            error("cmyk(0, 0, 0, 0)"),
+            ColorError::UnknownFunction("cmyk".to_string())
+        );
+        assert_eq!(
+            error("blurple"),
+            ColorError::UnknownName("blurple".to_string())
+        );
+    }
+
+    #[test]
+    fn oklch_round_trips() {
+        let colors = [
+            Color::BLACK,
+            Color::WHITE,
+            Color::rgb(255, 0, 0),
+            Color::rgb(0, 255, 0),
+            Color::rgb(0, 0, 255),
+            Color::rgb(255, 136, 0),
+            Color::rgb(0x66, 0x33, 0x99),
+            Color::rgb(12, 200, 180),
+        ];
+
+        for color in colors {
+            assert_close(Color::from_oklch(color.to_oklch()), color);
+        }
+    }
+
+    #[test]
+    fn colors_are_saved_as_hex() {
+        let color = Color::rgba(255, 136, 0, 128.0 / 255.0);
+        let json = serde_json::to_string(&color).unwrap();
+
+        assert_eq!(json, "\"#ff880080\"");
+        assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+        assert!(serde_json::from_str::<Color>("\"rgb(1, 2)\"").is_err());
+    }
+}
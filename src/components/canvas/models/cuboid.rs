@@ -0,0 +1,209 @@
+use super::super::frames::{CartCoord, GridCoord};
+use super::color::Color;
+use super::dimensions::Dimensions;
+use super::id::NodeId;
+use super::shape::{Bounds, Face, FaceType, Shape, Turn};
+use super::style::ShapeStyle;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Cuboid
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cuboid {
+    pub id: NodeId,
+    pub position: GridCoord,
+    pub dimensions: Dimensions,
+    pub style: ShapeStyle,
+}
+
+impl Cuboid {
+    pub fn new(id: NodeId, position: GridCoord, dimensions: Dimensions, color: Color) -> Self {
+        Self {
+            id,
+            position,
+            dimensions,
+            style: ShapeStyle {
+                color,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn with_style(mut self, style: ShapeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn vertices(&self, default_size: f64) -> ShapeVertices {
+        ShapeVertices::from_bounds(self.bounds_with_default(default_size))
+    }
+
+    fn bounds_with_default(&self, default_size: f64) -> Bounds {
+        let dims = self.dimensions.with_default(default_size);
+        let min: CartCoord = self.position.into();
+
+        Bounds::new(
+            min,
+            CartCoord::new(
+                min.x + dims.size_x,
+                min.y + dims.size_y,
+                min.z + dims.size_z,
+            ),
+        )
+    }
+}
+
+impl Shape for Cuboid {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.id = id;
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    fn faces(&self) -> Vec<Face> {
+        self.vertices(1.0).faces()
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.bounds_with_default(1.0)
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.position = self.position + offset;
+    }
+
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        self.position = self.bounds().turned(turn, pivot).min.into();
+        self.dimensions = self.dimensions.turned();
+        self.style.turn(turn);
+    }
+}
+
+// ============================================================================
+// Shape Vertices
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShapeVertices {
+    pub p1: CartCoord,
+    pub p2: CartCoord,
+    pub p3: CartCoord,
+    pub p4: CartCoord,
+    pub p5: CartCoord,
+    pub p6: CartCoord,
+    pub p7: CartCoord,
+    // Coin arrière bas, caché dans la vue par défaut
+    pub p8: CartCoord,
+}
+
+impl ShapeVertices {
+    // Sommets d'une boîte alignée sur les axes
+    pub fn from_bounds(bounds: Bounds) -> Self {
+        let Bounds { min, max } = bounds;
+
+        ShapeVertices {
+            p1: CartCoord::new(min.x, min.y, max.z),
+            p2: CartCoord::new(max.x, min.y, max.z),
+            p3: CartCoord::new(max.x, max.y, max.z),
+            p4: CartCoord::new(min.x, max.y, max.z),
+            p5: CartCoord::new(max.x, min.y, min.z),
+            p6: CartCoord::new(max.x, max.y, min.z),
+            p7: CartCoord::new(min.x, max.y, min.z),
+            p8: CartCoord::new(min.x, min.y, min.z),
+        }
+    }
+
+    // Les six faces, sommets dans le sens direct vus de l'extérieur
+    pub fn faces(&self) -> Vec<Face> {
+        vec![
+            Face::new(FaceType::Top, vec![self.p1, self.p2, self.p3, self.p4]),
+            Face::new(FaceType::Right, vec![self.p2, self.p5, self.p6, self.p3]),
+            Face::new(FaceType::Left, vec![self.p4, self.p3, self.p6, self.p7]),
+            Face::new(FaceType::Bottom, vec![self.p8, self.p7, self.p6, self.p5]),
+            Face::new(FaceType::BackLeft, vec![self.p1, self.p4, self.p7, self.p8]),
+            Face::new(
+                FaceType::BackRight,
+                vec![self.p1, self.p8, self.p5, self.p2],
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures::cuboid;
+    use super::*;
+
+    fn corners(face: &Face) -> Vec<(f64, f64, f64)> {
+        face.vertices.iter().map(|p| (p.x, p.y, p.z)).collect()
+    }
+
+    #[test]
+    fn face_vertices_follow_the_axes() {
+        let bounds = Bounds::new(CartCoord::new(0.0, 0.0, 0.0), CartCoord::new(1.0, 2.0, 3.0));
+        let faces = ShapeVertices::from_bounds(bounds).faces();
+        let expected = [
+            (
+                FaceType::Top,
+                [(0., 0., 3.), (1., 0., 3.), (1., 2., 3.), (0., 2., 3.)],
+            ),
+            (
+                FaceType::Right,
+                [(1., 0., 3.), (1., 0., 0.), (1., 2., 0.), (1., 2., 3.)],
+            ),
+            (
+                FaceType::Left,
+                [(0., 2., 3.), (1., 2., 3.), (1., 2., 0.), (0., 2., 0.)],
+            ),
+            (
+                FaceType::Bottom,
+                [(0., 0., 0.), (0., 2., 0.), (1., 2., 0.), (1., 0., 0.)],
+            ),
+            (
+                FaceType::BackLeft,
+                [(0., 0., 3.), (0., 2., 3.), (0., 2., 0.), (0., 0., 0.)],
+            ),
+            (
+                FaceType::BackRight,
+                [(0., 0., 3.), (0., 0., 0.), (1., 0., 0.), (1., 0., 3.)],
+            ),
+        ];
+
+        assert_eq!(faces.len(), expected.len());
+        for (face, (face_type, vertices)) in faces.iter().zip(expected) {
+            assert_eq!(face.face_type, face_type);
+            assert_eq!(corners(face), vertices);
+            // Le sens des sommets donne la face attendue
+            assert_eq!(FaceType::from_normal(face.normal()), face_type);
+        }
+    }
+
+    #[test]
+    fn size_z_is_the_height() {
+        let tower = cuboid((0.0, 0.0, 0.0), (1.0, 1.0, 9.0));
+        let Bounds { min, max } = tower.bounds();
+
+        assert_eq!(
+            (max.x - min.x, max.y - min.y, max.z - min.z),
+            (1.0, 1.0, 9.0)
+        );
+        let top = tower
+            .faces()
+            .into_iter()
+            .find(|face| face.face_type == FaceType::Top)
+            .unwrap();
+        assert!(top.vertices.iter().all(|p| p.z == 9.0));
+    }
+}
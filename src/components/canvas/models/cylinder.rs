@@ -0,0 +1,127 @@
+use super::super::frames::{CartCoord, GridCoord};
+use super::color::Color;
+use super::id::NodeId;
+use super::shape::{Bounds, Curvature, Face, FaceType, Shape, Turn};
+use super::style::ShapeStyle;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+// ============================================================================
+// Cylinder
+// ============================================================================
+
+// Nombre de facettes du flanc : assez pour que le dessus paraisse une ellipse
+const SEGMENTS: usize = 48;
+
+// Cylindre vertical posé sur la grille. `position` est le coin bas de la
+// boîte englobante, comme pour le pavé : le centre de la base est à
+// (x + radius, y + radius)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cylinder {
+    pub id: NodeId,
+    pub position: GridCoord,
+    // Rayon et hauteur en cases
+    pub radius: f64,
+    pub height: f64,
+    pub style: ShapeStyle,
+}
+
+impl Cylinder {
+    pub fn new(id: NodeId, position: GridCoord, radius: f64, height: f64, color: Color) -> Self {
+        Self {
+            id,
+            position,
+            radius,
+            height,
+            style: ShapeStyle {
+                color,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn with_style(mut self, style: ShapeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn center(&self) -> CartCoord {
+        let position: CartCoord = self.position.into();
+        position + CartCoord::new(self.radius, self.radius, 0.0)
+    }
+
+    // Points du cercle de base à l'altitude `z`, dans le sens direct
+    fn circle(&self, z: f64) -> Vec<CartCoord> {
+        let center = self.center();
+
+        (0..SEGMENTS)
+            .map(|i| {
+                let (sin, cos) = (TAU * i as f64 / SEGMENTS as f64).sin_cos();
+                CartCoord::new(
+                    center.x + self.radius * cos,
+                    center.y + self.radius * sin,
+                    center.z + z,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Shape for Cylinder {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.id = id;
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // Dessus et dessous en polygones réguliers, flanc en facettes
+    // rectangulaires marquées comme surface courbe
+    fn faces(&self) -> Vec<Face> {
+        let bottom = self.circle(0.0);
+        let top = self.circle(self.height);
+
+        let mut faces = vec![
+            Face::new(FaceType::Top, top.clone()),
+            Face::new(FaceType::Bottom, bottom.iter().rev().copied().collect()),
+        ];
+        faces.extend((0..SEGMENTS).map(|i| {
+            let j = (i + 1) % SEGMENTS;
+            Face::new(FaceType::Curved, vec![bottom[i], bottom[j], top[j], top[i]])
+        }));
+        faces
+    }
+
+    fn bounds(&self) -> Bounds {
+        let min: CartCoord = self.position.into();
+        let diameter = 2.0 * self.radius;
+
+        Bounds::new(
+            min,
+            CartCoord::new(min.x + diameter, min.y + diameter, min.z + self.height),
+        )
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.position = self.position + offset;
+    }
+
+    // Symétrique autour de son axe : seule la position change
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        self.position = self.bounds().turned(turn, pivot).min.into();
+        self.style.turn(turn);
+    }
+
+    fn curvature(&self) -> Option<Curvature> {
+        Some(Curvature::Vertical)
+    }
+}
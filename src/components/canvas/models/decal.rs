@@ -0,0 +1,72 @@
+use super::shape::Direction;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Decal
+// ============================================================================
+
+// Image plaquée sur une face, étirée sur toute l'étendue de la face dans son
+// repère : elle suit la forme quand celle-ci est déplacée ou redimensionnée.
+// L'image est embarquée (data URL) pour que les projets et les exports SVG
+// restent autonomes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decal {
+    pub source: String,
+    #[serde(default = "default_opacity")]
+    pub opacity: f64,
+    // Sens de la largeur de l'image sur le dessus ou le dessous d'une forme
+    #[serde(default)]
+    pub direction: Direction,
+}
+
+fn default_opacity() -> f64 {
+    1.0
+}
+
+impl Decal {
+    pub fn new(source: String) -> Self {
+        Self {
+            source,
+            opacity: default_opacity(),
+            direction: Direction::default(),
+        }
+    }
+
+    // Image importée : PNG, JPEG, SVG… selon le type MIME
+    pub fn from_bytes(bytes: &[u8], mime: &str) -> Self {
+        Self::new(format!("data:{mime};base64,{}", base64(bytes)))
+    }
+}
+
+// Type MIME d'une image d'après l'extension de son nom de fichier
+pub fn image_mime(name: &str) -> &'static str {
+    let extension = name.rsplit('.').next().unwrap_or("").to_ascii_lowercase();
+    match extension.as_str() {
+        "svg" => "image/svg+xml",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "image/png",
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
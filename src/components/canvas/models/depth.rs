@@ -0,0 +1,438 @@
+use super::super::frames::{Camera, IsoCoord};
+use super::bsp::{bsp_order, Fragment};
+use super::shape::{Bounds, Face, Shape};
+use std::collections::HashMap;
+
+// Tolérance pour les formes qui se touchent exactement
+const EPSILON: f64 = 1e-6;
+
+// Taille en cases de la grille des carrés de l'écran qui répartit les
+// rectangles à comparer
+const BUCKET_CELLS: f64 = 4.0;
+
+// ============================================================================
+// Depth Sorting
+// ============================================================================
+
+// Vrai si `a` est entièrement derrière `b` le long d'au moins un axe.
+// L'observateur regarde depuis +x, +y, +z : ce qui a les plus petites
+// coordonnées est le plus loin.
+fn is_behind(a: &Bounds, b: &Bounds) -> bool {
+    a.max.x <= b.min.x + EPSILON || a.max.y <= b.min.y + EPSILON || a.max.z <= b.min.z + EPSILON
+}
+
+// Deux boîtes ne peuvent se cacher l'une l'autre que si leurs projections
+// se chevauchent à l'écran
+fn overlaps_on_screen(a: &Bounds, b: &Bounds, camera: &Camera) -> bool {
+    let (a_min, a_max) = a.iso_extent(camera);
+    let (b_min, b_max) = b.iso_extent(camera);
+
+    a_min.x < b_max.x - EPSILON
+        && b_min.x < a_max.x - EPSILON
+        && a_min.y < b_max.y - EPSILON
+        && b_min.y < a_max.y - EPSILON
+}
+
+// Somme des coordonnées du centre, utilisée pour départager les formes
+// sans relation d'occlusion ou prises dans un cycle
+fn depth_key(bounds: &Bounds) -> f64 {
+    (bounds.min.x + bounds.max.x) + (bounds.min.y + bounds.max.y) + (bounds.min.z + bounds.max.z)
+}
+
+pub struct DepthOrder {
+    // Indices dans la liste de formes, du plus lointain au plus proche
+    pub order: Vec<usize>,
+    // Vrai si un cycle d'occlusion a dû être cassé arbitrairement
+    pub cyclic: bool,
+}
+
+// Ordre de dessin par tri topologique du graphe "est derrière" entre
+// formes qui se chevauchent à l'écran
+pub fn depth_order<S: Shape>(shapes: &[S], camera: &Camera) -> DepthOrder {
+    let world: Vec<Bounds> = shapes.iter().map(|shape| shape.bounds()).collect();
+    bounds_order(&world, camera)
+}
+
+// Ordre de dessin des faces d'une forme non convexe, qui peuvent se cacher
+// entre elles
+pub fn face_order(faces: &[Face], camera: &Camera) -> Vec<usize> {
+    let world: Vec<Bounds> = faces.iter().map(Face::bounds).collect();
+    bounds_order(&world, camera).order
+}
+
+fn bounds_order(world: &[Bounds], camera: &Camera) -> DepthOrder {
+    // Les comparaisons de profondeur se font dans le repère de la vue
+    let bounds: Vec<Bounds> = world.iter().map(|b| b.in_view(&camera.view)).collect();
+    let count = bounds.len();
+
+    // in_front[i] contient les formes à dessiner après i
+    let mut in_front: Vec<Vec<usize>> = vec![Vec::new(); count];
+    let mut pending = vec![0usize; count];
+
+    for i in 0..count {
+        for j in (i + 1)..count {
+            if !overlaps_on_screen(&world[i], &world[j], camera) {
+                continue;
+            }
+
+            let (back, front) = match (
+                is_behind(&bounds[i], &bounds[j]),
+                is_behind(&bounds[j], &bounds[i]),
+            ) {
+                (true, false) => (i, j),
+                (false, true) => (j, i),
+                // Formes qui s'interpénètrent : l'ordre Z de la liste départage
+                _ => (i, j),
+            };
+
+            in_front[back].push(front);
+            pending[front] += 1;
+        }
+    }
+
+    let mut order = Vec::with_capacity(count);
+    let mut done = vec![false; count];
+    let mut cyclic = false;
+
+    while order.len() < count {
+        // Parmi les formes prêtes, la plus lointaine d'abord ; s'il n'y en a
+        // aucune, le graphe contient un cycle et on le casse sur la forme
+        // qui a le moins de prédécesseurs restants
+        let next = (0..count)
+            .filter(|&i| !done[i])
+            .min_by(|&a, &b| {
+                pending[a]
+                    .cmp(&pending[b])
+                    .then(depth_key(&bounds[a]).total_cmp(&depth_key(&bounds[b])))
+                    .then(a.cmp(&b))
+            })
+            .expect("il reste au moins une forme à placer");
+
+        cyclic |= pending[next] > 0;
+        done[next] = true;
+        order.push(next);
+
+        for &front in &in_front[next] {
+            pending[front] = pending[front].saturating_sub(1);
+        }
+    }
+
+    DepthOrder { order, cyclic }
+}
+
+// Deux polygones convexes de l'écran se recouvrent (plus qu'un bord commun)
+// si aucune de leurs arêtes ne les sépare
+pub fn polygons_overlap(a: &[IsoCoord], b: &[IsoCoord]) -> bool {
+    !has_separating_edge(a, a, b) && !has_separating_edge(b, a, b)
+}
+
+fn has_separating_edge(edges: &[IsoCoord], a: &[IsoCoord], b: &[IsoCoord]) -> bool {
+    let range = |polygon: &[IsoCoord], axis: (f64, f64)| {
+        polygon
+            .iter()
+            .map(|p| p.x * axis.0 + p.y * axis.1)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), d| {
+                (lo.min(d), hi.max(d))
+            })
+    };
+
+    (0..edges.len()).any(|i| {
+        let (p, q) = (edges[i], edges[(i + 1) % edges.len()]);
+        let length = (q.x - p.x).hypot(q.y - p.y);
+        if length < EPSILON {
+            return false;
+        }
+        let axis = ((q.y - p.y) / length, (p.x - q.x) / length);
+        let ((a_lo, a_hi), (b_lo, b_hi)) = (range(a, axis), range(b, axis));
+        a_hi <= b_lo + EPSILON || b_hi <= a_lo + EPSILON
+    })
+}
+
+// Ordre de dessin de rectangles alignés sur les axes qui ne se traversent
+// pas (faces fusionnées d'un modèle voxel). Deux tels rectangles sont séparés
+// le long d'un axe : quand leurs projections se recouvrent vraiment, un seul
+// des deux est derrière l'autre et l'ordre obtenu est exact. Renvoie None si
+// les rectangles se cachent en cycle
+pub fn rect_order(faces: &[Face], camera: &Camera) -> Option<Vec<usize>> {
+    let bounds: Vec<Bounds> = faces
+        .iter()
+        .map(|face| face.bounds().in_view(&camera.view))
+        .collect();
+    let screen: Vec<Vec<IsoCoord>> = faces.iter().map(|face| face.iso_vertices(camera)).collect();
+    let extents: Vec<(IsoCoord, IsoCoord)> = screen
+        .iter()
+        .map(|polygon| screen_extent(polygon))
+        .collect();
+
+    // Seuls les rectangles d'un même carré de l'écran sont comparés
+    let size = camera.grid.size * BUCKET_CELLS;
+    let bucket = |p: IsoCoord| ((p.x / size).floor() as i64, (p.y / size).floor() as i64);
+    let mut buckets: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (index, &(min, max)) in extents.iter().enumerate() {
+        let ((i0, j0), (i1, j1)) = (bucket(min), bucket(max));
+        for i in i0..=i1 {
+            for j in j0..=j1 {
+                buckets.entry((i, j)).or_default().push(index);
+            }
+        }
+    }
+
+    let count = faces.len();
+    let mut in_front: Vec<Vec<usize>> = vec![Vec::new(); count];
+    let mut pending = vec![0usize; count];
+
+    for (&key, members) in &buckets {
+        for (n, &i) in members.iter().enumerate() {
+            for &j in &members[n + 1..] {
+                // Une paire n'est comparée que dans le carré du coin de
+                // l'intersection de ses deux étendues
+                let corner = IsoCoord::new(
+                    extents[i].0.x.max(extents[j].0.x),
+                    extents[i].0.y.max(extents[j].0.y),
+                );
+                if bucket(corner) != key || !polygons_overlap(&screen[i], &screen[j]) {
+                    continue;
+                }
+
+                let (back, front) = match (
+                    is_behind(&bounds[i], &bounds[j]),
+                    is_behind(&bounds[j], &bounds[i]),
+                ) {
+                    (true, false) => (i, j),
+                    (false, true) => (j, i),
+                    // Rectangles qui se touchent seulement
+                    _ => continue,
+                };
+                in_front[back].push(front);
+                pending[front] += 1;
+            }
+        }
+    }
+
+    let mut ready: Vec<usize> = (0..count).filter(|&i| pending[i] == 0).collect();
+    let mut order = Vec::with_capacity(count);
+    while let Some(next) = ready.pop() {
+        order.push(next);
+        for &front in &in_front[next] {
+            pending[front] -= 1;
+            if pending[front] == 0 {
+                ready.push(front);
+            }
+        }
+    }
+
+    (order.len() == count).then_some(order)
+}
+
+// Rectangle (min, max) occupé par un polygone de l'écran
+fn screen_extent(polygon: &[IsoCoord]) -> (IsoCoord, IsoCoord) {
+    polygon.iter().fold(
+        (
+            IsoCoord::new(f64::INFINITY, f64::INFINITY),
+            IsoCoord::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |(min, max), p| {
+            (
+                IsoCoord::new(min.x.min(p.x), min.y.min(p.y)),
+                IsoCoord::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    )
+}
+
+// ============================================================================
+// Render Order
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthMode {
+    // Tri des formes entières uniquement
+    Shapes,
+    // Tri des formes, avec repli sur le découpage des faces en cas de cycle
+    #[default]
+    Auto,
+    // Découpage systématique des faces
+    Faces,
+}
+
+pub enum RenderOrder {
+    Shapes(Vec<usize>),
+    Fragments(Vec<Fragment>),
+}
+
+pub fn render_order<S: Shape>(shapes: &[S], mode: DepthMode, camera: &Camera) -> RenderOrder {
+    if mode != DepthMode::Faces {
+        let depth = depth_order(shapes, camera);
+        if !depth.cyclic || mode == DepthMode::Shapes {
+            return RenderOrder::Shapes(depth.order);
+        }
+    }
+
+    let fragments = shapes
+        .iter()
+        .enumerate()
+        .flat_map(|(index, shape)| {
+            shape
+                .visible_faces(camera)
+                .into_iter()
+                .map(move |face| Fragment::new(index, face))
+        })
+        .collect();
+
+    RenderOrder::Fragments(bsp_order(fragments, camera.view_direction()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::frames::GridCoord;
+    use super::super::color::Color;
+    use super::super::fixtures::{cube, cuboid};
+    use super::super::id::NodeId;
+    use super::super::voxel::VoxelModel;
+    use super::*;
+
+    #[test]
+    fn adjacent_cuboids_are_drawn_back_to_front() {
+        let camera = Camera::default();
+
+        for step in [(1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, 1.0)] {
+            let back = cube((0.0, 0.0, 0.0));
+            let front = cube(step);
+            assert!(is_behind(&back.bounds(), &front.bounds()));
+            assert!(!is_behind(&front.bounds(), &back.bounds()));
+
+            let depth = depth_order(&[front, back], &camera);
+            assert_eq!(depth.order, vec![1, 0], "voisin le long de {step:?}");
+            assert!(!depth.cyclic);
+        }
+    }
+
+    #[test]
+    fn turning_the_view_turns_the_order() {
+        let mut camera = Camera::default();
+        camera.view.rotate_clockwise();
+        camera.view.rotate_clockwise();
+
+        // Vu de l'autre côté, le voisin en +x passe derrière
+        let shapes = [cube((0.0, 0.0, 0.0)), cube((1.0, 0.0, 0.0))];
+        assert_eq!(depth_order(&shapes, &camera).order, vec![1, 0]);
+    }
+
+    #[test]
+    fn stacked_shapes_are_drawn_bottom_up() {
+        let shapes = [
+            cube((1.0, 1.0, 2.0)),
+            cube((1.0, 1.0, 1.0)),
+            cuboid((0.0, 0.0, 0.0), (3.0, 3.0, 1.0)),
+        ];
+
+        let depth = depth_order(&shapes, &Camera::default());
+
+        assert_eq!(depth.order, vec![2, 1, 0]);
+        assert!(!depth.cyclic);
+    }
+
+    #[test]
+    fn occlusion_cycles_are_broken() {
+        // Trois barres en tourniquet : chacune cache la suivante
+        let bars = [
+            cuboid((0.0, 0.0, 1.0), (3.0, 1.0, 1.0)),
+            cuboid((0.0, 1.0, 0.0), (1.0, 1.0, 3.0)),
+            cuboid((1.0, 0.0, 0.0), (1.0, 3.0, 1.0)),
+        ];
+        let bounds: Vec<Bounds> = bars.iter().map(Shape::bounds).collect();
+        for (back, front) in [(0, 1), (1, 2), (2, 0)] {
+            assert!(is_behind(&bounds[back], &bounds[front]));
+            assert!(!is_behind(&bounds[front], &bounds[back]));
+        }
+
+        let depth = depth_order(&bars, &Camera::default());
+
+        // Le cycle est cassé sur la première barre, la suite respecte le graphe
+        assert!(depth.cyclic);
+        assert_eq!(depth.order, vec![0, 1, 2]);
+    }
+
+    // Aucune face dessinée avant une autre qu'elle recouvre à l'écran ne
+    // doit être devant elle
+    fn assert_back_to_front(drawn: &[Face], camera: &Camera) {
+        for (i, first) in drawn.iter().enumerate() {
+            for then in &drawn[i + 1..] {
+                if polygons_overlap(&first.iso_vertices(camera), &then.iso_vertices(camera)) {
+                    assert!(
+                        !is_behind(
+                            &then.bounds().in_view(&camera.view),
+                            &first.bounds().in_view(&camera.view)
+                        ),
+                        "{:?} dessinée avant {:?}",
+                        first.bounds(),
+                        then.bounds()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rectangles_are_drawn_back_to_front_in_every_view() {
+        // Un L surmonté d'un cube
+        let cubes = [
+            cube((0.0, 0.0, 0.0)),
+            cube((1.0, 0.0, 0.0)),
+            cube((0.0, 1.0, 0.0)),
+            cube((0.0, 0.0, 1.0)),
+        ];
+        let mut camera = Camera::default();
+        for _ in 0..4 {
+            let faces: Vec<Face> = cubes
+                .iter()
+                .flat_map(|cube| cube.visible_faces(&camera))
+                .collect();
+            let order = rect_order(&faces, &camera).expect("pas de cycle");
+
+            let drawn: Vec<Face> = order.into_iter().map(|i| faces[i].clone()).collect();
+            assert_back_to_front(&drawn, &camera);
+            camera.view.rotate_clockwise();
+        }
+    }
+
+    #[test]
+    fn voxel_models_are_drawn_back_to_front_in_every_view() {
+        // Escalier bicolore posé sur une dalle
+        let red = Color::new(1.0, 0.0, 0.0, 1.0);
+        let mut model = VoxelModel::new(NodeId::new(), GridCoord::new(0.0, 0.0, 0.0));
+        for x in 0..4 {
+            for y in 0..3 {
+                model.voxels.insert((x, y, 0), Color::WHITE);
+                for z in 1..=x {
+                    let color = if y == 1 { red } else { Color::WHITE };
+                    model.voxels.insert((x, y, z), color);
+                }
+            }
+        }
+
+        let mut camera = Camera::default();
+        for _ in 0..4 {
+            let drawn = model.drawn_faces(&camera);
+            assert!(drawn.len() < model.visible_faces(&camera).len());
+            assert_back_to_front(&drawn, &camera);
+            camera.view.rotate_clockwise();
+        }
+    }
+
+    #[test]
+    fn hiding_rectangles_in_a_cycle_have_no_order() {
+        let bars = [
+            cuboid((0.0, 0.0, 1.0), (3.0, 1.0, 1.0)),
+            cuboid((0.0, 1.0, 0.0), (1.0, 1.0, 3.0)),
+            cuboid((1.0, 0.0, 0.0), (1.0, 3.0, 1.0)),
+        ];
+        let camera = Camera::default();
+        let faces: Vec<Face> = bars
+            .iter()
+            .flat_map(|bar| bar.visible_faces(&camera))
+            .collect();
+
+        assert_eq!(rect_order(&faces, &camera), None);
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Dimensions
+// ============================================================================
+
+// Taille en cases le long de chaque axe du monde, fractions de case possibles
+// avec les subdivisions. z est l'axe vertical : une tour de 1×1×9 a
+// `size_z = 9`. Les anciens fichiers nommaient ces champs width (x), height
+// (y) et depth (z) ; ces noms restent acceptés à la lecture
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Dimensions {
+    #[serde(alias = "width")]
+    pub size_x: f64,
+    #[serde(alias = "height")]
+    pub size_y: f64,
+    #[serde(alias = "depth")]
+    pub size_z: f64,
+}
+
+impl Dimensions {
+    pub fn new(size_x: f64, size_y: f64, size_z: f64) -> Self {
+        Self {
+            size_x,
+            size_y,
+            size_z,
+        }
+    }
+
+    // Empreinte tournée d'un quart de tour : tailles en x et en y échangées
+    pub fn turned(self) -> Self {
+        Self {
+            size_x: self.size_y,
+            size_y: self.size_x,
+            size_z: self.size_z,
+        }
+    }
+
+    pub fn with_default(self, default_size: f64) -> Self {
+        let or_default = |size: f64| if size > 0.0 { size } else { default_size };
+
+        Self {
+            size_x: or_default(self.size_x),
+            size_y: or_default(self.size_y),
+            size_z: or_default(self.size_z),
+        }
+    }
+}
+
+impl Default for Dimensions {
+    fn default() -> Self {
+        Self {
+            size_x: 1.0,
+            size_y: 1.0,
+            size_z: 1.0,
+        }
+    }
+}
@@ -0,0 +1,760 @@
+use super::super::frames::{Camera, CartCoord, GridCoord, IsoCoord, Projection};
+use super::super::grid::Grid;
+use super::decal::Decal;
+use super::depth::depth_order;
+use super::folder::{Folder, Node};
+use super::id::NodeId;
+use super::label::Label;
+use super::light::Light;
+use super::shape::{AnyShape, Face, FaceType, Shape, Turn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// ============================================================================
+// Layer
+// ============================================================================
+
+// Calque : liste ordonnée de formes et de dossiers, de l'arrière vers l'avant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layer {
+    pub name: String,
+    pub nodes: Vec<Node>,
+    pub visible: bool,
+    pub locked: bool,
+}
+
+impl Layer {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            nodes: Vec::new(),
+            visible: true,
+            locked: false,
+        }
+    }
+
+    // Formes visibles du calque, avec les décalages des dossiers appliqués
+    pub fn visible_shapes(&self) -> Vec<AnyShape> {
+        let mut shapes = Vec::new();
+        for node in &self.nodes {
+            node.collect_visible(GridCoord::new(0.0, 0.0, 0.0), &mut shapes);
+        }
+        shapes
+    }
+
+    // Textes visibles du calque, avec les décalages des dossiers appliqués
+    pub fn visible_labels(&self) -> Vec<Label> {
+        let mut labels = Vec::new();
+        for node in &self.nodes {
+            node.collect_labels(GridCoord::new(0.0, 0.0, 0.0), &mut labels);
+        }
+        labels
+    }
+}
+
+// ============================================================================
+// Node Path
+// ============================================================================
+
+// Chemin d'un nœud : calque, puis positions successives dans les dossiers.
+// Le dernier indice est la position du nœud dans son conteneur.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodePath {
+    pub layer: usize,
+    pub indices: Vec<usize>,
+}
+
+impl NodePath {
+    fn parents(&self) -> &[usize] {
+        &self.indices[..self.indices.len() - 1]
+    }
+
+    fn index(&self) -> usize {
+        self.indices[self.indices.len() - 1]
+    }
+
+    // Vrai si `other` est ce nœud ou se trouve quelque part dans son contenu
+    fn contains(&self, other: &NodePath) -> bool {
+        self.layer == other.layer && other.indices.starts_with(&self.indices)
+    }
+}
+
+fn find_in(nodes: &[Node], id: NodeId, path: &mut Vec<usize>) -> bool {
+    for (index, node) in nodes.iter().enumerate() {
+        path.push(index);
+        if node.id() == id {
+            return true;
+        }
+        if let Node::Folder(folder) = node {
+            if find_in(&folder.children, id, path) {
+                return true;
+            }
+        }
+        path.pop();
+    }
+    false
+}
+
+// ============================================================================
+// Document
+// ============================================================================
+
+// Les calques sont dessinés dans l'ordre : le dernier passe par-dessus les autres
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    pub layers: Vec<Layer>,
+    pub active_layer: usize,
+    #[serde(default)]
+    pub projection: Projection,
+    #[serde(default)]
+    pub grid: Grid,
+    #[serde(default)]
+    pub light: Light,
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Self {
+            layers: vec![Layer::new("Layer 1".to_string())],
+            active_layer: 0,
+            projection: Projection::default(),
+            grid: Grid::default(),
+            light: Light::default(),
+        }
+    }
+
+    // ========= Calques ========= //
+
+    pub fn add_layer(&mut self, name: String) -> usize {
+        self.layers.push(Layer::new(name));
+        self.active_layer = self.layers.len() - 1;
+        self.active_layer
+    }
+
+    // Le document garde toujours au moins un calque
+    pub fn remove_layer(&mut self, index: usize) -> Option<Layer> {
+        if self.layers.len() <= 1 || index >= self.layers.len() {
+            return None;
+        }
+
+        let layer = self.layers.remove(index);
+        // Le calque actif reste le même, sauf s'il vient d'être supprimé
+        if index < self.active_layer {
+            self.active_layer -= 1;
+        }
+        self.active_layer = self.active_layer.min(self.layers.len() - 1);
+        Some(layer)
+    }
+
+    // Ajoute les calques d'un autre document. Un nœud dont un identifiant
+    // existe déjà (même fichier importé deux fois) reçoit de nouveaux identifiants.
+    // Les textes importés restent accrochés aux formes renommées.
+    pub fn merge(&mut self, other: Document) {
+        let mut existing: HashSet<NodeId> = self
+            .layers
+            .iter()
+            .flat_map(|layer| layer.nodes.iter().flat_map(Node::ids))
+            .collect();
+        let mut renamed = HashMap::new();
+        let mut layers = other.layers;
+
+        for layer in &mut layers {
+            for node in &mut layer.nodes {
+                if node.ids().iter().any(|id| existing.contains(id)) {
+                    renamed.extend(node.reassign_ids());
+                }
+                existing.extend(node.ids());
+            }
+        }
+
+        for mut layer in layers {
+            for node in &mut layer.nodes {
+                node.remap_anchors(&renamed);
+            }
+            self.layers.push(layer);
+        }
+    }
+
+    pub fn move_layer(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.layers.len() || to >= self.layers.len() {
+            return false;
+        }
+
+        let layer = self.layers.remove(from);
+        self.layers.insert(to, layer);
+        self.active_layer = to;
+        true
+    }
+
+    // ========= Recherche ========= //
+
+    pub fn find(&self, id: NodeId) -> Option<NodePath> {
+        self.layers.iter().enumerate().find_map(|(layer, l)| {
+            let mut indices = Vec::new();
+            find_in(&l.nodes, id, &mut indices).then_some(NodePath { layer, indices })
+        })
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        let path = self.find(id)?;
+        Some(&self.container(path.layer, path.parents())[path.index()])
+    }
+
+    pub fn shape(&self, id: NodeId) -> Option<&AnyShape> {
+        match self.node(id)? {
+            Node::Shape(shape) => Some(shape),
+            _ => None,
+        }
+    }
+
+    // Accès en écriture à une forme, même rangée dans un dossier,
+    // tant qu'aucun de ses parents n'est verrouillé
+    pub fn shape_mut(&mut self, id: NodeId) -> Option<&mut AnyShape> {
+        let path = self.find_unlocked(id)?;
+        match &mut self.container_mut(path.layer, path.parents())[path.index()] {
+            Node::Shape(shape) => Some(shape),
+            _ => None,
+        }
+    }
+
+    pub fn folder_mut(&mut self, id: NodeId) -> Option<&mut Folder> {
+        let path = self.find(id)?;
+        match &mut self.container_mut(path.layer, path.parents())[path.index()] {
+            Node::Folder(folder) => Some(folder),
+            _ => None,
+        }
+    }
+
+    // Décalage cumulé des dossiers qui contiennent le nœud
+    pub fn offset_of(&self, id: NodeId) -> Option<GridCoord> {
+        let path = self.find(id)?;
+        let mut nodes = &self.layers[path.layer].nodes;
+        let mut offset = GridCoord::new(0.0, 0.0, 0.0);
+
+        for &index in path.parents() {
+            if let Node::Folder(folder) = &nodes[index] {
+                offset = offset + folder.offset;
+                nodes = &folder.children;
+            }
+        }
+        Some(offset)
+    }
+
+    // Formes des calques visibles, dans l'ordre des calques
+    pub fn visible_shapes(&self) -> Vec<AnyShape> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.visible)
+            .flat_map(Layer::visible_shapes)
+            .collect()
+    }
+
+    // Face visible au premier plan sous un point de l'écran
+    pub fn face_at(&self, point: IsoCoord, camera: &Camera) -> Option<(NodeId, FaceType)> {
+        self.face_under(point, camera)
+            .map(|(id, face)| (id, face.face_type))
+    }
+
+    // Idem, avec la face elle-même en coordonnées du monde. Les faces d'une
+    // forme non convexe peuvent se recouvrir : la plus proche l'emporte
+    pub fn face_under(&self, point: IsoCoord, camera: &Camera) -> Option<(NodeId, Face)> {
+        self.layers
+            .iter()
+            .rev()
+            .filter(|layer| layer.visible)
+            .find_map(|layer| {
+                let shapes = layer.visible_shapes();
+                let order = depth_order(&shapes, camera).order;
+
+                order.into_iter().rev().find_map(|index| {
+                    let shape = &shapes[index];
+                    let faces = shape.visible_faces(camera);
+                    let faces = if shape.is_convex() {
+                        faces
+                    } else {
+                        shape.sort_faces(faces, camera)
+                    };
+
+                    faces
+                        .into_iter()
+                        .rev()
+                        .find(|face| face.contains(point, camera))
+                        .map(|face| (shape.id(), face))
+                })
+            })
+    }
+
+    // Identifiant pour une nouvelle forme ou un nouveau dossier
+    pub fn new_id(&self) -> NodeId {
+        loop {
+            let id = NodeId::new();
+            if self.find(id).is_none() {
+                return id;
+            }
+        }
+    }
+
+    // ========= Formes et dossiers ========= //
+
+    // Ajoute le nœud au premier plan du calque actif
+    pub fn add_shape(&mut self, shape: AnyShape) -> bool {
+        self.add_node_to(self.active_layer, shape.into())
+    }
+
+    pub fn add_label(&mut self, label: Label) -> bool {
+        self.add_node_to(self.active_layer, label.into())
+    }
+
+    pub fn add_node_to(&mut self, layer: usize, node: Node) -> bool {
+        match self.layers.get_mut(layer) {
+            Some(layer) if !layer.locked => {
+                layer.nodes.push(node);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn add_to_folder(&mut self, folder: NodeId, node: Node) -> bool {
+        let Some(path) = self.find_unlocked(folder) else {
+            return false;
+        };
+
+        match &mut self.container_mut(path.layer, path.parents())[path.index()] {
+            Node::Folder(folder) => {
+                folder.children.push(node);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn remove(&mut self, id: NodeId) -> Option<Node> {
+        let path = self.find_unlocked(id)?;
+        Some(
+            self.container_mut(path.layer, path.parents())
+                .remove(path.index()),
+        )
+    }
+
+    // Regroupe des nœuds dans un nouveau dossier, placé là où se trouvait le premier
+    pub fn group(&mut self, ids: &[NodeId], name: String) -> Option<NodeId> {
+        let (&first, others) = ids.split_first()?;
+        let paths = ids
+            .iter()
+            .map(|&id| self.find_unlocked(id))
+            .collect::<Option<Vec<_>>>()?;
+
+        // Un nœud et son dossier parent ne peuvent pas être regroupés ensemble :
+        // le retirer du document emporterait aussi l'autre
+        let nested = paths.iter().enumerate().any(|(i, outer)| {
+            paths
+                .iter()
+                .enumerate()
+                .any(|(j, inner)| i != j && outer.contains(inner))
+        });
+        if nested {
+            return None;
+        }
+
+        // Le dossier prend la place du premier nœud, puis reçoit les autres
+        let folder_id = self.new_id();
+        let target = self.offset_of(first)?;
+        let path = self.find(first)?;
+        let container = self.container_mut(path.layer, path.parents());
+        let node = container.remove(path.index());
+        container.insert(
+            path.index(),
+            Folder::new(folder_id, name)
+                .with_children(vec![node])
+                .into(),
+        );
+
+        for &id in others {
+            let Some(from) = self.offset_of(id) else {
+                continue;
+            };
+            if let Some(mut node) = self.remove(id) {
+                // Les décalages des dossiers quittés et rejoints se compensent :
+                // le nœud ne bouge pas dans le monde
+                node.translate(offset_between(from, target));
+                self.add_to_folder(folder_id, node);
+            }
+        }
+        Some(folder_id)
+    }
+
+    // Défait un dossier : son contenu reprend sa place, décalage appliqué
+    pub fn ungroup(&mut self, id: NodeId) -> bool {
+        let Some(path) = self.find_unlocked(id) else {
+            return false;
+        };
+
+        let container = self.container_mut(path.layer, path.parents());
+        if !matches!(container[path.index()], Node::Folder(_)) {
+            return false;
+        }
+        let Node::Folder(folder) = container.remove(path.index()) else {
+            return false;
+        };
+
+        let children = folder.children.into_iter().map(|mut child| {
+            child.translate(folder.offset);
+            child
+        });
+        container.splice(path.index()..path.index(), children);
+        true
+    }
+
+    // Déplace une forme, ou un dossier avec tout son contenu
+    pub fn move_by(&mut self, id: NodeId, offset: GridCoord) -> bool {
+        let Some(path) = self.find_unlocked(id) else {
+            return false;
+        };
+
+        self.container_mut(path.layer, path.parents())[path.index()].translate(offset);
+        true
+    }
+
+    // Tourne des nœuds d'un quart de tour autour d'un même pivot, par défaut
+    // le centre de leur empreinte commune. Les textes accrochés aux formes
+    // tournées changent de face avec elles
+    pub fn rotate(&mut self, ids: &[NodeId], turn: Turn, pivot: Option<CartCoord>) -> bool {
+        if ids.is_empty() || ids.iter().any(|&id| self.find_unlocked(id).is_none()) {
+            return false;
+        }
+
+        let pivot = match pivot {
+            Some(pivot) => pivot,
+            None => {
+                let bounds = ids
+                    .iter()
+                    .filter_map(|&id| self.node(id)?.bounds(self.offset_of(id)?))
+                    .reduce(|a, b| a.union(&b));
+                match bounds {
+                    Some(bounds) => bounds.footprint_center(),
+                    None => return false,
+                }
+            }
+        };
+
+        let mut rotated = HashSet::new();
+        for &id in ids {
+            let (Some(path), Some(offset)) = (self.find(id), self.offset_of(id)) else {
+                continue;
+            };
+            let node = &mut self.container_mut(path.layer, path.parents())[path.index()];
+            rotated.extend(node.ids());
+            node.rotate(turn, pivot - offset.into());
+        }
+
+        for layer in &mut self.layers {
+            for node in &mut layer.nodes {
+                node.follow_turn(&rotated, turn);
+            }
+        }
+        true
+    }
+
+    // Copie le nœud juste devant l'original, avec de nouveaux identifiants
+    pub fn duplicate(&mut self, id: NodeId) -> Option<NodeId> {
+        let path = self.find_unlocked(id)?;
+
+        let container = self.container_mut(path.layer, path.parents());
+        let mut copy = container[path.index()].clone();
+        copy.reassign_ids();
+        let copy_id = copy.id();
+        container.insert(path.index() + 1, copy);
+        Some(copy_id)
+    }
+
+    pub fn set_visible(&mut self, id: NodeId, visible: bool) -> bool {
+        match self.folder_mut(id) {
+            Some(folder) => {
+                folder.visible = visible;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Le verrou d'un dossier reste modifiable même quand il est verrouillé
+    pub fn set_locked(&mut self, id: NodeId, locked: bool) -> bool {
+        match self.folder_mut(id) {
+            Some(folder) => {
+                folder.locked = locked;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Plaque une image sur une face, ou la retire avec `None`. L'image est
+    // rangée par type de face : elle ne va que sur une face plane dont le
+    // type n'appartient qu'à elle
+    pub fn attach_decal(&mut self, id: NodeId, face_type: FaceType, decal: Option<Decal>) -> bool {
+        let Some(shape) = self.shape_mut(id) else {
+            return false;
+        };
+
+        match decal {
+            None => shape.style_mut().clear_decal(face_type),
+            Some(decal) if face_type != FaceType::Curved && shape.has_own_style(face_type) => {
+                shape.style_mut().face_mut(face_type).decal = Some(decal);
+            }
+            Some(_) => return false,
+        }
+        true
+    }
+
+    // ========= Ordre Z ========= //
+
+    pub fn bring_to_front(&mut self, id: NodeId) -> bool {
+        self.reorder(id, |_, last| last)
+    }
+
+    pub fn send_to_back(&mut self, id: NodeId) -> bool {
+        self.reorder(id, |_, _| 0)
+    }
+
+    pub fn bring_forward(&mut self, id: NodeId) -> bool {
+        self.reorder(id, |index, last| (index + 1).min(last))
+    }
+
+    pub fn send_backward(&mut self, id: NodeId) -> bool {
+        self.reorder(id, |index, _| index.saturating_sub(1))
+    }
+
+    // Déplace le nœud au premier plan d'un autre calque
+    pub fn move_to_layer(&mut self, id: NodeId, target: usize) -> bool {
+        match self.layers.get(target) {
+            Some(layer) if !layer.locked => {}
+            _ => return false,
+        }
+
+        let Some(from) = self.offset_of(id) else {
+            return false;
+        };
+        match self.remove(id) {
+            Some(mut node) => {
+                // À la racine du calque, le nœud garde les décalages de ses
+                // anciens dossiers
+                node.translate(from);
+                self.layers[target].nodes.push(node);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // ========= Outils internes ========= //
+
+    fn container(&self, layer: usize, parents: &[usize]) -> &Vec<Node> {
+        let mut nodes = &self.layers[layer].nodes;
+        for &index in parents {
+            match &nodes[index] {
+                Node::Folder(folder) => nodes = &folder.children,
+                _ => unreachable!("le chemin ne traverse que des dossiers"),
+            }
+        }
+        nodes
+    }
+
+    fn container_mut(&mut self, layer: usize, parents: &[usize]) -> &mut Vec<Node> {
+        let mut nodes = &mut self.layers[layer].nodes;
+        for &index in parents {
+            match &mut nodes[index] {
+                Node::Folder(folder) => nodes = &mut folder.children,
+                _ => unreachable!("le chemin ne traverse que des dossiers"),
+            }
+        }
+        nodes
+    }
+
+    // Un nœud est verrouillé si son calque, un dossier parent ou lui-même l'est
+    fn is_locked(&self, path: &NodePath) -> bool {
+        let layer = &self.layers[path.layer];
+        let mut nodes = &layer.nodes;
+        let mut locked = layer.locked;
+
+        for &index in &path.indices {
+            if let Node::Folder(folder) = &nodes[index] {
+                locked |= folder.locked;
+                nodes = &folder.children;
+            }
+        }
+        locked
+    }
+
+    fn find_unlocked(&self, id: NodeId) -> Option<NodePath> {
+        self.find(id).filter(|path| !self.is_locked(path))
+    }
+
+    // `new_index` reçoit la position actuelle et la dernière position du conteneur
+    fn reorder(&mut self, id: NodeId, new_index: impl Fn(usize, usize) -> usize) -> bool {
+        let Some(path) = self.find_unlocked(id) else {
+            return false;
+        };
+
+        let nodes = self.container_mut(path.layer, path.parents());
+        let node = nodes.remove(path.index());
+        nodes.insert(new_index(path.index(), nodes.len()), node);
+        true
+    }
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Translation qui ramène un nœud du décalage `from` au décalage `to`
+fn offset_between(from: GridCoord, to: GridCoord) -> GridCoord {
+    GridCoord::new(from.x - to.x, from.y - to.y, from.z - to.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures::cube;
+    use super::super::shape::Bounds;
+    use super::*;
+
+    fn three_layers() -> Document {
+        let mut document = Document::new();
+        document.layers[0].name = "A".to_string();
+        document.add_layer("B".to_string());
+        document.add_layer("C".to_string());
+        document
+    }
+
+    #[test]
+    fn remove_layer_below_keeps_active_layer() {
+        let mut document = three_layers();
+        document.active_layer = 1;
+
+        document.remove_layer(0);
+
+        assert_eq!(document.layers[document.active_layer].name, "B");
+    }
+
+    #[test]
+    fn remove_active_layer_selects_a_neighbour() {
+        let mut document = three_layers();
+
+        document.remove_layer(2);
+
+        assert_eq!(document.active_layer, 1);
+        assert_eq!(document.layers[document.active_layer].name, "B");
+    }
+
+    fn add_cube(document: &mut Document) -> NodeId {
+        let shape = cube((0.0, 0.0, 0.0));
+        let id = shape.id;
+        document.add_shape(shape.into());
+        id
+    }
+
+    // Dossier décalé à la racine du premier calque, contenant un cube
+    fn offset_cube(document: &mut Document, offset: GridCoord) -> NodeId {
+        let shape = cube((1.0, 0.0, 0.0));
+        let id = shape.id;
+        let mut folder = Folder::new(document.new_id(), "Dossier".to_string())
+            .with_children(vec![Node::Shape(shape.into())]);
+        folder.offset = offset;
+        document.layers[0].nodes.push(folder.into());
+        id
+    }
+
+    fn world_bounds(document: &Document) -> Vec<Bounds> {
+        document
+            .visible_shapes()
+            .iter()
+            .map(|shape| shape.bounds())
+            .collect()
+    }
+
+    #[test]
+    fn group_rejects_a_node_with_its_parent_folder() {
+        let mut document = Document::new();
+        let child = add_cube(&mut document);
+        let parent = document.group(&[child], "Parent".to_string()).unwrap();
+        let before = document.clone();
+
+        assert_eq!(document.group(&[child, parent], "Group".to_string()), None);
+        assert_eq!(document.group(&[parent, child], "Group".to_string()), None);
+        assert_eq!(document, before);
+    }
+
+    #[test]
+    fn group_moves_every_node_into_the_folder() {
+        let mut document = Document::new();
+        let a = add_cube(&mut document);
+        let b = add_cube(&mut document);
+
+        let folder = document.group(&[a, b], "Group".to_string()).unwrap();
+
+        assert_eq!(document.layers[0].nodes.len(), 1);
+        assert_eq!(document.find(a).unwrap().indices, vec![0, 0]);
+        assert_eq!(document.find(b).unwrap().indices, vec![0, 1]);
+        assert_eq!(document.layers[0].nodes[0].id(), folder);
+    }
+
+    #[test]
+    fn grouping_keeps_nodes_in_place() {
+        let mut document = Document::new();
+        let a = offset_cube(&mut document, GridCoord::new(2.0, 0.0, 0.0));
+        let b = offset_cube(&mut document, GridCoord::new(0.0, 3.0, 1.0));
+        let before = world_bounds(&document);
+
+        document.group(&[a, b], "Group".to_string()).unwrap();
+
+        assert_eq!(document.offset_of(a), document.offset_of(b));
+        assert_eq!(world_bounds(&document), before);
+    }
+
+    #[test]
+    fn moving_to_another_layer_keeps_nodes_in_place() {
+        let mut document = Document::new();
+        document.add_layer("B".to_string());
+        let id = offset_cube(&mut document, GridCoord::new(2.0, -1.0, 1.0));
+        let before = world_bounds(&document);
+
+        assert!(document.move_to_layer(id, 1));
+
+        assert_eq!(document.find(id).unwrap().layer, 1);
+        assert_eq!(world_bounds(&document), before);
+    }
+
+    #[test]
+    fn decals_only_go_on_flat_faces() {
+        let mut document = Document::new();
+        let id = add_cube(&mut document);
+        let decal = Decal::new("image.png".to_string());
+
+        assert!(document.attach_decal(id, FaceType::Top, Some(decal.clone())));
+        assert!(!document.attach_decal(id, FaceType::Curved, Some(decal.clone())));
+        let style = document.shape(id).unwrap().style();
+        assert_eq!(style.decal(FaceType::Top), Some(&decal));
+        assert_eq!(style.decal(FaceType::Curved), None);
+
+        assert!(document.attach_decal(id, FaceType::Top, None));
+        assert!(document.shape(id).unwrap().style().faces.is_empty());
+    }
+
+    #[test]
+    fn rotating_several_shapes_turns_them_about_their_common_center() {
+        let mut document = Document::new();
+        let a = cube((0.0, 0.0, 0.0));
+        let b = cube((2.0, 0.0, 0.0));
+        let ids = [a.id, b.id];
+        document.add_shape(a.into());
+        document.add_shape(b.into());
+
+        assert!(document.rotate(&ids, Turn::Clockwise, None));
+
+        let footprint = |bounds: &Bounds| (bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y);
+        let turned: Vec<_> = world_bounds(&document).iter().map(footprint).collect();
+        assert_eq!(turned, vec![(1.0, -1.0, 2.0, 0.0), (1.0, 1.0, 2.0, 2.0)]);
+    }
+}
@@ -0,0 +1,189 @@
+use super::super::frames::{CartCoord, GridCoord};
+use super::color::Color;
+use super::id::NodeId;
+use super::shape::{Bounds, Face, FaceType, Shape, Turn};
+use super::style::ShapeStyle;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Extrusion
+// ============================================================================
+
+// Polygone quelconque tracé sur la grille (L, hexagone, emprise d'un
+// bâtiment) et extrudé verticalement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Extrusion {
+    pub id: NodeId,
+    pub position: GridCoord,
+    // Contour au sol en cases, relatif à `position`, dans un sens ou dans
+    // l'autre
+    pub footprint: Vec<(f64, f64)>,
+    pub height: f64,
+    pub style: ShapeStyle,
+}
+
+impl Extrusion {
+    pub fn new(
+        id: NodeId,
+        position: GridCoord,
+        footprint: Vec<(f64, f64)>,
+        height: f64,
+        color: Color,
+    ) -> Self {
+        Self {
+            id,
+            position,
+            footprint,
+            height,
+            style: ShapeStyle {
+                color,
+                ..Default::default()
+            },
+        }
+    }
+
+    // Contour tracé en coordonnées absolues de la grille, ramené à son coin
+    // bas comme position
+    pub fn from_outline(id: NodeId, outline: &[GridCoord], height: f64, color: Color) -> Self {
+        let min_x = outline.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let min_y = outline.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+        let z = outline.first().map_or(0.0, |p| p.z);
+
+        let footprint = outline.iter().map(|p| (p.x - min_x, p.y - min_y)).collect();
+        Self::new(
+            id,
+            GridCoord::new(min_x, min_y, z),
+            footprint,
+            height,
+            color,
+        )
+    }
+
+    pub fn with_style(mut self, style: ShapeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    // Contour au sol dans le sens direct, à l'altitude `z` au-dessus de la base
+    fn ring(&self, z: f64) -> Vec<CartCoord> {
+        let area: f64 = (0..self.footprint.len())
+            .map(|i| {
+                let (a, b) = (
+                    self.footprint[i],
+                    self.footprint[(i + 1) % self.footprint.len()],
+                );
+                a.0 * b.1 - b.0 * a.1
+            })
+            .sum();
+
+        let points = self
+            .footprint
+            .iter()
+            .map(|&(x, y)| (self.position + GridCoord::new(x, y, z)).into());
+        if area < 0.0 {
+            points.rev().collect()
+        } else {
+            points.collect()
+        }
+    }
+}
+
+impl Shape for Extrusion {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.id = id;
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // Dessus en un seul polygone, dessous, et un mur par côté du contour
+    fn faces(&self) -> Vec<Face> {
+        if self.footprint.len() < 3 {
+            return Vec::new();
+        }
+
+        let bottom = self.ring(0.0);
+        let top = self.ring(self.height);
+        let count = bottom.len();
+
+        let mut faces = vec![
+            Face::new(FaceType::Top, top.clone()),
+            Face::new(FaceType::Bottom, bottom.iter().rev().copied().collect()),
+        ];
+        faces.extend((0..count).map(|i| {
+            let j = (i + 1) % count;
+            Face::from_vertices(vec![bottom[i], bottom[j], top[j], top[i]])
+        }));
+        faces
+    }
+
+    fn bounds(&self) -> Bounds {
+        let (min_x, max_x, min_y, max_y) = self.footprint.iter().fold(
+            (
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(min_x, max_x, min_y, max_y), &(x, y)| {
+                (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+            },
+        );
+
+        let origin: CartCoord = self.position.into();
+        if self.footprint.is_empty() {
+            return Bounds::new(origin, origin);
+        }
+        Bounds::new(
+            origin + CartCoord::new(min_x, min_y, 0.0),
+            origin + CartCoord::new(max_x, max_y, self.height),
+        )
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.position = self.position + offset;
+    }
+
+    // Le contour est tourné point par point, puis ramené à son coin bas
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        let points: Vec<CartCoord> = self
+            .footprint
+            .iter()
+            .map(|&(x, y)| turn.apply((self.position + GridCoord::new(x, y, 0.0)).into(), pivot))
+            .collect();
+        let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let min_y = points.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+
+        if !points.is_empty() {
+            self.position = GridCoord::new(min_x, min_y, self.position.z);
+        }
+        self.footprint = points.iter().map(|p| (p.x - min_x, p.y - min_y)).collect();
+        self.style.turn(turn);
+    }
+
+    // Contour rentrant (L, U) : les murs peuvent se cacher entre eux
+    fn is_convex(&self) -> bool {
+        let count = self.footprint.len();
+        let turns: Vec<f64> = (0..count)
+            .map(|i| {
+                let (a, b, c) = (
+                    self.footprint[i],
+                    self.footprint[(i + 1) % count],
+                    self.footprint[(i + 2) % count],
+                );
+                (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+            })
+            .collect();
+
+        turns.iter().all(|&t| t >= -1e-9) || turns.iter().all(|&t| t <= 1e-9)
+    }
+}
@@ -0,0 +1,101 @@
+use super::color::Color;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+// ============================================================================
+// Fill
+// ============================================================================
+
+// Remplissage d'une face autre qu'une couleur unie. Les coordonnées sont
+// celles du repère de la face (`SurfaceFrame`), en cases : le motif est
+// peint sur la surface et suit son inclinaison à l'écran
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Fill {
+    // Dégradé sur toute la face, `angle` en degrés depuis l'axe u
+    Linear {
+        stops: Vec<GradientStop>,
+        #[serde(default)]
+        angle: f64,
+    },
+    // Dégradé depuis le centre de la face
+    Radial {
+        stops: Vec<GradientStop>,
+    },
+    // Motif répété, dessiné avec `color` sur la couleur de la forme
+    Pattern {
+        pattern: PatternKind,
+        color: Color,
+        #[serde(default = "default_scale")]
+        scale: f64,
+        #[serde(default)]
+        angle: f64,
+    },
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    // Position entre 0 et 1
+    pub offset: f64,
+    pub color: Color,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatternKind {
+    Stripes,
+    Bricks,
+    Tiles,
+    Dots,
+}
+
+impl GradientStop {
+    pub fn new(offset: f64, color: Color) -> Self {
+        Self {
+            offset: offset.clamp(0.0, 1.0),
+            color,
+        }
+    }
+}
+
+impl Fill {
+    // Dégradé régulier entre deux couleurs
+    pub fn linear(from: Color, to: Color, angle: f64) -> Self {
+        Fill::Linear {
+            stops: vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+            angle,
+        }
+    }
+
+    pub fn radial(center: Color, edge: Color) -> Self {
+        Fill::Radial {
+            stops: vec![GradientStop::new(0.0, center), GradientStop::new(1.0, edge)],
+        }
+    }
+
+    pub fn pattern(pattern: PatternKind, color: Color) -> Self {
+        Fill::Pattern {
+            pattern,
+            color,
+            scale: default_scale(),
+            angle: 0.0,
+        }
+    }
+
+    // Identifiant du modèle SVG partagé par toutes les faces qui utilisent
+    // ce remplissage sur la même couleur de fond
+    pub fn template_id(&self, base: Color) -> String {
+        let key = match self {
+            Fill::Pattern { .. } => format!("{self:?}{base:?}"),
+            _ => format!("{self:?}"),
+        };
+
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        format!("fill-{:016x}", hasher.finish())
+    }
+}
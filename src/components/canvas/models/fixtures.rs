@@ -0,0 +1,22 @@
+use super::super::frames::GridCoord;
+use super::color::Color;
+use super::cuboid::Cuboid;
+use super::dimensions::Dimensions;
+use super::id::NodeId;
+
+// Formes prêtes à l'emploi pour les tests
+
+// Pavé blanc : coin d'origine et tailles en cases
+pub fn cuboid(position: (f64, f64, f64), size: (f64, f64, f64)) -> Cuboid {
+    Cuboid::new(
+        NodeId::new(),
+        GridCoord::new(position.0, position.1, position.2),
+        Dimensions::new(size.0, size.1, size.2),
+        Color::WHITE,
+    )
+}
+
+// Cube d'une case
+pub fn cube(position: (f64, f64, f64)) -> Cuboid {
+    cuboid(position, (1.0, 1.0, 1.0))
+}
@@ -0,0 +1,217 @@
+use super::super::frames::{CartCoord, GridCoord};
+use super::id::NodeId;
+use super::label::{Label, LabelAnchor};
+use super::shape::{AnyShape, Bounds, Shape, Turn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// ============================================================================
+// Node
+// ============================================================================
+
+// Élément d'un calque ou d'un dossier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "node")]
+pub enum Node {
+    Shape(AnyShape),
+    Folder(Folder),
+    Label(Label),
+}
+
+impl Node {
+    pub fn id(&self) -> NodeId {
+        match self {
+            Node::Shape(shape) => shape.id(),
+            Node::Folder(folder) => folder.id,
+            Node::Label(label) => label.id,
+        }
+    }
+
+    pub fn translate(&mut self, offset: GridCoord) {
+        match self {
+            Node::Shape(shape) => shape.translate(offset),
+            Node::Folder(folder) => folder.offset = folder.offset + offset,
+            Node::Label(label) => label.translate(offset),
+        }
+    }
+
+    // Quart de tour autour de `pivot`, donné dans le repère du nœud : le
+    // contenu d'un dossier tourne autour du pivot ramené à son décalage
+    pub fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        match self {
+            Node::Shape(shape) => shape.rotate(turn, pivot),
+            Node::Folder(folder) => {
+                let pivot = pivot - folder.offset.into();
+                for child in &mut folder.children {
+                    child.rotate(turn, pivot);
+                }
+            }
+            Node::Label(label) => label.rotate(turn, pivot),
+        }
+    }
+
+    // Fait suivre aux textes accrochés la rotation de leurs formes
+    pub fn follow_turn(&mut self, shapes: &HashSet<NodeId>, turn: Turn) {
+        match self {
+            Node::Shape(_) => {}
+            Node::Folder(folder) => {
+                for child in &mut folder.children {
+                    child.follow_turn(shapes, turn);
+                }
+            }
+            Node::Label(label) => label.follow_turn(shapes, turn),
+        }
+    }
+
+    // Boîte englobante des formes et des textes libres du nœud, décalée par
+    // les dossiers parents
+    pub fn bounds(&self, offset: GridCoord) -> Option<Bounds> {
+        match self {
+            Node::Shape(shape) => Some(shape.translated(offset).bounds()),
+            Node::Folder(folder) => folder
+                .children
+                .iter()
+                .filter_map(|child| child.bounds(offset + folder.offset))
+                .reduce(|a, b| a.union(&b)),
+            Node::Label(label) => match label.anchor {
+                LabelAnchor::Plane { position, .. } => {
+                    let point: CartCoord = (position + offset).into();
+                    Some(Bounds::new(point, point))
+                }
+                LabelAnchor::Face { .. } => None,
+            },
+        }
+    }
+
+    // Donne de nouveaux identifiants au nœud et à tout son contenu, pour une
+    // copie. Les textes accrochés à une forme copiée suivent la copie ; la
+    // correspondance ancien -> nouveau identifiant est renvoyée
+    pub fn reassign_ids(&mut self) -> HashMap<NodeId, NodeId> {
+        let mut ids = HashMap::new();
+        self.assign_new_ids(&mut ids);
+        self.remap_anchors(&ids);
+        ids
+    }
+
+    fn assign_new_ids(&mut self, ids: &mut HashMap<NodeId, NodeId>) {
+        let new_id = NodeId::new();
+        ids.insert(self.id(), new_id);
+
+        match self {
+            Node::Shape(shape) => shape.set_id(new_id),
+            Node::Folder(folder) => {
+                folder.id = new_id;
+                for child in &mut folder.children {
+                    child.assign_new_ids(ids);
+                }
+            }
+            Node::Label(label) => label.id = new_id,
+        }
+    }
+
+    // Raccroche les textes aux formes renommées
+    pub fn remap_anchors(&mut self, ids: &HashMap<NodeId, NodeId>) {
+        match self {
+            Node::Shape(_) => {}
+            Node::Folder(folder) => {
+                for child in &mut folder.children {
+                    child.remap_anchors(ids);
+                }
+            }
+            Node::Label(label) => label.remap(ids),
+        }
+    }
+
+    // Identifiants du nœud et de tous ses descendants
+    pub fn ids(&self) -> Vec<NodeId> {
+        match self {
+            Node::Shape(shape) => vec![shape.id()],
+            Node::Label(label) => vec![label.id],
+            Node::Folder(folder) => std::iter::once(folder.id)
+                .chain(folder.children.iter().flat_map(Node::ids))
+                .collect(),
+        }
+    }
+
+    // Ajoute les formes visibles, décalées par les dossiers parents
+    pub fn collect_visible(&self, offset: GridCoord, out: &mut Vec<AnyShape>) {
+        match self {
+            Node::Shape(shape) => out.push(shape.translated(offset)),
+            Node::Folder(folder) if folder.visible => {
+                for child in &folder.children {
+                    child.collect_visible(offset + folder.offset, out);
+                }
+            }
+            Node::Folder(_) | Node::Label(_) => {}
+        }
+    }
+
+    // Ajoute les textes visibles ; les textes libres sont décalés par les
+    // dossiers parents, les textes accrochés suivent déjà leur forme
+    pub fn collect_labels(&self, offset: GridCoord, out: &mut Vec<Label>) {
+        match self {
+            Node::Label(label) => {
+                let mut label = label.clone();
+                label.translate(offset);
+                out.push(label);
+            }
+            Node::Folder(folder) if folder.visible => {
+                for child in &folder.children {
+                    child.collect_labels(offset + folder.offset, out);
+                }
+            }
+            Node::Shape(_) | Node::Folder(_) => {}
+        }
+    }
+}
+
+impl From<AnyShape> for Node {
+    fn from(shape: AnyShape) -> Self {
+        Node::Shape(shape)
+    }
+}
+
+impl From<Label> for Node {
+    fn from(label: Label) -> Self {
+        Node::Label(label)
+    }
+}
+
+impl From<Folder> for Node {
+    fn from(folder: Folder) -> Self {
+        Node::Folder(folder)
+    }
+}
+
+// ============================================================================
+// Folder
+// ============================================================================
+
+// Groupe de formes et de dossiers, avec son propre décalage dans la grille
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: NodeId,
+    pub name: String,
+    pub offset: GridCoord,
+    pub children: Vec<Node>,
+    pub visible: bool,
+    pub locked: bool,
+}
+
+impl Folder {
+    pub fn new(id: NodeId, name: String) -> Self {
+        Self {
+            id,
+            name,
+            offset: GridCoord::new(0.0, 0.0, 0.0),
+            children: Vec::new(),
+            visible: true,
+            locked: false,
+        }
+    }
+
+    pub fn with_children(mut self, children: Vec<Node>) -> Self {
+        self.children = children;
+        self
+    }
+}
@@ -0,0 +1,358 @@
+use super::super::frames::{Camera, CartCoord, GridCoord};
+use super::color::Color;
+use super::id::NodeId;
+use super::shape::{Direction, FaceType, Shape, SurfaceFrame, Turn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// ============================================================================
+// Label
+// ============================================================================
+
+// Texte posé à plat sur un plan isométrique ou sur une face de forme
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    pub id: NodeId,
+    pub text: String,
+    pub anchor: LabelAnchor,
+    #[serde(default)]
+    pub style: TextStyle,
+}
+
+// Plans isométriques de base, nommés comme les faces visibles d'un cube
+// dans la vue par défaut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LabelPlane {
+    // Plan horizontal (x, y)
+    Top,
+    // Plan vertical (y, z), face à l'axe x
+    Right,
+    // Plan vertical (x, z), face à l'axe y
+    Left,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum LabelAnchor {
+    // Plan libre passant par `position`, qui est le point d'ancrage du texte.
+    // `direction` est le sens de lecture sur le plan horizontal
+    Plane {
+        plane: LabelPlane,
+        position: GridCoord,
+        #[serde(default)]
+        direction: Direction,
+    },
+    // Face d'une forme : le texte suit la forme. `offset` est le décalage
+    // (u, v) en cases depuis le centre de la face, `direction` le sens de
+    // lecture sur le dessus ou le dessous
+    Face {
+        shape: NodeId,
+        face: FaceType,
+        #[serde(default)]
+        offset: (f64, f64),
+        #[serde(default)]
+        direction: Direction,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextAlign {
+    Start,
+    #[default]
+    Middle,
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextStyle {
+    pub font_family: String,
+    // Taille en cases, pour suivre le zoom et la taille de grille
+    pub font_size: f64,
+    pub color: Color,
+    pub bold: bool,
+    pub italic: bool,
+    pub align: TextAlign,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            font_family: "sans-serif".to_string(),
+            font_size: 0.3,
+            color: Color::BLACK,
+            bold: false,
+            italic: false,
+            align: TextAlign::Middle,
+        }
+    }
+}
+
+// Texte prêt à dessiner : repère de la surface (u dans le sens de lecture
+// à l'écran) et point d'ancrage dans ce repère
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedLabel {
+    pub label: Label,
+    pub frame: SurfaceFrame,
+    pub position: (f64, f64),
+}
+
+impl LabelPlane {
+    fn frame(&self, origin: CartCoord, direction: Direction) -> SurfaceFrame {
+        let (u, v) = match self {
+            LabelPlane::Top => {
+                let u = direction.unit();
+                (u, CartCoord::new(-u.y, u.x, 0.0))
+            }
+            LabelPlane::Right => (
+                CartCoord::new(0.0, -1.0, 0.0),
+                CartCoord::new(0.0, 0.0, -1.0),
+            ),
+            LabelPlane::Left => (
+                CartCoord::new(1.0, 0.0, 0.0),
+                CartCoord::new(0.0, 0.0, -1.0),
+            ),
+        };
+        SurfaceFrame { origin, u, v }
+    }
+}
+
+impl LabelAnchor {
+    // Accroche à une face de forme. Le texte retrouve sa face par son type :
+    // une face dont le type se répète sur la forme est refusée
+    pub fn on_face<S: Shape>(shape: &S, face: FaceType) -> Option<Self> {
+        shape.has_own_style(face).then(|| LabelAnchor::Face {
+            shape: shape.id(),
+            face,
+            offset: (0.0, 0.0),
+            direction: Direction::default(),
+        })
+    }
+}
+
+impl Label {
+    pub fn new(id: NodeId, text: String, anchor: LabelAnchor) -> Self {
+        Self {
+            id,
+            text,
+            anchor,
+            style: TextStyle::default(),
+        }
+    }
+
+    pub fn with_style(mut self, style: TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    // Forme à laquelle le texte est accroché
+    pub fn attached_to(&self) -> Option<NodeId> {
+        match self.anchor {
+            LabelAnchor::Face { shape, .. } => Some(shape),
+            LabelAnchor::Plane { .. } => None,
+        }
+    }
+
+    // Un texte accroché suit sa forme : seul un texte libre se déplace
+    pub fn translate(&mut self, offset: GridCoord) {
+        if let LabelAnchor::Plane { position, .. } = &mut self.anchor {
+            *position = *position + offset;
+        }
+    }
+
+    // Quart de tour d'un texte libre autour de `pivot` : au sol, le sens de
+    // lecture tourne ; un texte vertical passe sur l'autre plan vertical
+    pub fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        if let LabelAnchor::Plane {
+            plane,
+            position,
+            direction,
+        } = &mut self.anchor
+        {
+            *position = turn.apply((*position).into(), pivot).into();
+            match plane {
+                LabelPlane::Top => *direction = direction.turned(turn),
+                LabelPlane::Right => *plane = LabelPlane::Left,
+                LabelPlane::Left => *plane = LabelPlane::Right,
+            }
+        }
+    }
+
+    // Un texte accroché à une forme tournée passe sur la face qui a pris la
+    // place de la sienne. Sur le dessus et le dessous, le sens de lecture
+    // tourne : le décalage, pris dans ce sens, suit alors la forme
+    pub fn follow_turn(&mut self, shapes: &HashSet<NodeId>, turn: Turn) {
+        let LabelAnchor::Face {
+            shape,
+            face,
+            direction,
+            ..
+        } = &mut self.anchor
+        else {
+            return;
+        };
+        if !shapes.contains(shape) {
+            return;
+        }
+
+        if matches!(face, FaceType::Top | FaceType::Bottom) {
+            *direction = direction.turned(turn);
+        }
+        *face = face.turned(turn);
+    }
+
+    // Suit les nouveaux identifiants des formes copiées
+    pub fn remap(&mut self, ids: &HashMap<NodeId, NodeId>) {
+        if let LabelAnchor::Face { shape, .. } = &mut self.anchor {
+            if let Some(&new_id) = ids.get(shape) {
+                *shape = new_id;
+            }
+        }
+    }
+
+    // Place le texte pour la caméra. Un texte accroché n'est placé que si sa
+    // forme existe, que le type de sa face ne se répète pas et que la face
+    // est tournée vers l'observateur
+    pub fn place<S: Shape>(&self, shapes: &[S], camera: &Camera) -> Option<PlacedLabel> {
+        let (frame, position) = match &self.anchor {
+            LabelAnchor::Plane {
+                plane,
+                position,
+                direction,
+            } => (plane.frame((*position).into(), *direction), (0.0, 0.0)),
+            LabelAnchor::Face {
+                shape,
+                face,
+                offset,
+                direction,
+            } => {
+                let shape = shapes
+                    .iter()
+                    .find(|s| s.id() == *shape)
+                    .filter(|s| s.has_own_style(*face))?;
+                let face = shape
+                    .visible_faces(camera)
+                    .into_iter()
+                    .find(|f| f.face_type == *face)?;
+                let frame = face.oriented_frame(*direction);
+
+                // Centre de l'étendue de la face dans son repère
+                let local: Vec<(f64, f64)> =
+                    face.vertices.iter().map(|&p| frame.local(p)).collect();
+                let (min_u, max_u) = local
+                    .iter()
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), p| {
+                        (a.min(p.0), b.max(p.0))
+                    });
+                let (min_v, max_v) = local
+                    .iter()
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), p| {
+                        (a.min(p.1), b.max(p.1))
+                    });
+                let center = (
+                    (min_u + max_u) / 2.0 + offset.0,
+                    (min_v + max_v) / 2.0 + offset.1,
+                );
+                (frame, center)
+            }
+        };
+
+        Some(readable(self.clone(), frame, position, camera))
+    }
+}
+
+// Le texte doit rester lisible quelle que soit la vue : u est inversé si le
+// repère apparaît en miroir à l'écran (plan vu de dos, vue retournée), puis
+// le repère est retourné si le texte se lirait de droite à gauche
+fn readable(
+    label: Label,
+    frame: SurfaceFrame,
+    position: (f64, f64),
+    camera: &Camera,
+) -> PlacedLabel {
+    let (mut frame, mut position) = (frame, position);
+
+    let (u, v) = (camera.project(frame.u), camera.project(frame.v));
+    if u.x * v.y - u.y * v.x < 0.0 {
+        frame.u = frame.u * -1.0;
+        position.0 = -position.0;
+    }
+
+    if camera.project(frame.u).x < 0.0 {
+        frame.u = frame.u * -1.0;
+        frame.v = frame.v * -1.0;
+        position = (-position.0, -position.1);
+    }
+
+    PlacedLabel {
+        label,
+        frame,
+        position,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::document::Document;
+    use super::super::fixtures::cuboid;
+    use super::*;
+
+    fn placed(document: &Document) -> PlacedLabel {
+        let shapes = document.visible_shapes();
+        document.layers[0].visible_labels()[0]
+            .place(&shapes, &Camera::default())
+            .unwrap()
+    }
+
+    // Point d'ancrage du texte dans le monde
+    fn anchor_point(placed: &PlacedLabel) -> CartCoord {
+        let (u, v) = placed.position;
+        placed.frame.origin + placed.frame.u * u + placed.frame.v * v
+    }
+
+    #[test]
+    fn face_labels_sit_at_the_center_of_their_face() {
+        let mut document = Document::new();
+        let shape = cuboid((1.0, 2.0, 0.0), (2.0, 2.0, 3.0));
+        let anchor = LabelAnchor::on_face(&shape, FaceType::Top).unwrap();
+        document.add_shape(shape.into());
+        document.add_label(Label::new(document.new_id(), "A".to_string(), anchor));
+
+        assert_eq!(
+            anchor_point(&placed(&document)),
+            CartCoord::new(2.0, 3.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn top_face_label_turns_with_its_shape() {
+        let mut document = Document::new();
+        let shape = cuboid((0.0, 0.0, 0.0), (2.0, 1.0, 1.0));
+        let cube = shape.id;
+        document.add_shape(shape.into());
+        let anchor = LabelAnchor::Face {
+            shape: cube,
+            face: FaceType::Top,
+            offset: (0.5, 0.0),
+            direction: Direction::default(),
+        };
+        document.add_label(Label::new(document.new_id(), "A".to_string(), anchor));
+
+        let before = placed(&document);
+        assert_eq!(before.frame.u.y, 0.0);
+        assert_eq!(before.frame.u.x.abs(), 1.0);
+
+        // Le texte lu le long de x se lit le long de y une fois la forme tournée
+        assert!(document.rotate(&[cube], Turn::Clockwise, None));
+        let after = placed(&document);
+        assert!(after.frame.u.x.abs() < 1e-9);
+        assert!((after.frame.u.y.abs() - 1.0).abs() < 1e-9);
+
+        // Le texte reste au même endroit de la forme
+        let pivot = CartCoord::new(1.0, 0.5, 0.0);
+        let expected = Turn::Clockwise.apply(anchor_point(&before), pivot);
+        let moved = anchor_point(&after);
+        assert!((moved.x - expected.x).abs() < 1e-9);
+        assert!((moved.y - expected.y).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,167 @@
+use super::super::frames::CartCoord;
+use super::color::{Color, Oklch};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// ============================================================================
+// Light
+// ============================================================================
+
+// Lumière directionnelle de la scène, fixe dans le repère du monde : en
+// tournant la vue, les faces éclairées restent les mêmes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "LightFile")]
+pub struct Light {
+    // Direction vers la source de lumière, normalisée
+    pub direction: CartCoord,
+    // Part de la lumière directe, entre 0 et 1
+    pub intensity: f64,
+    // Lumière reçue par toutes les faces, même à l'ombre, entre 0 et 1
+    pub ambient: f64,
+    // Ombres portées sur le sol et sur le dessus des formes plus basses
+    #[serde(default)]
+    pub cast_shadows: bool,
+    #[serde(default = "default_shadow_opacity")]
+    pub shadow_opacity: f64,
+}
+
+fn default_shadow_opacity() -> f64 {
+    0.25
+}
+
+// Lumière telle qu'écrite dans le fichier : la direction est renormalisée et
+// les réglages ramenés entre 0 et 1, comme avec `Light::new`
+#[derive(Deserialize)]
+struct LightFile {
+    direction: CartCoord,
+    intensity: f64,
+    ambient: f64,
+    #[serde(default)]
+    cast_shadows: bool,
+    #[serde(default = "default_shadow_opacity")]
+    shadow_opacity: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LightError {
+    InvalidDirection,
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for LightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LightError::InvalidDirection => write!(f, "light direction must be a non-zero vector"),
+            LightError::InvalidValue(field) => write!(f, "light {field} must be a number"),
+        }
+    }
+}
+
+impl std::error::Error for LightError {}
+
+impl TryFrom<LightFile> for Light {
+    type Error = LightError;
+
+    fn try_from(file: LightFile) -> Result<Self, LightError> {
+        let length = file.direction.length();
+        if !length.is_finite() || length == 0.0 {
+            return Err(LightError::InvalidDirection);
+        }
+        for (field, value) in [
+            ("intensity", file.intensity),
+            ("ambient", file.ambient),
+            ("shadow opacity", file.shadow_opacity),
+        ] {
+            if value.is_nan() {
+                return Err(LightError::InvalidValue(field));
+            }
+        }
+
+        Ok(Self {
+            cast_shadows: file.cast_shadows,
+            shadow_opacity: file.shadow_opacity.clamp(0.0, 1.0),
+            ..Self::new(file.direction, file.intensity, file.ambient)
+        })
+    }
+}
+
+impl Light {
+    pub fn new(direction: CartCoord, intensity: f64, ambient: f64) -> Self {
+        Self {
+            direction: direction.normalized(),
+            intensity: intensity.clamp(0.0, 1.0),
+            ambient: ambient.clamp(0.0, 1.0),
+            cast_shadows: false,
+            shadow_opacity: default_shadow_opacity(),
+        }
+    }
+
+    // Direction donnée par un azimut (depuis l'axe x, vers l'axe y) et une
+    // hauteur au-dessus de l'horizon, en degrés
+    pub fn from_angles(azimuth: f64, elevation: f64, intensity: f64, ambient: f64) -> Self {
+        let (azimuth, elevation) = (azimuth.to_radians(), elevation.to_radians());
+        let direction = CartCoord::new(
+            elevation.cos() * azimuth.cos(),
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+        );
+        Self::new(direction, intensity, ambient)
+    }
+
+    // Tourne la source autour de l'axe vertical
+    pub fn rotate(&mut self, degrees: f64) {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let CartCoord { x, y, z } = self.direction;
+        self.direction = CartCoord::new(x * cos - y * sin, x * sin + y * cos, z);
+    }
+
+    // Facteur d'éclairement d'une face de normale `normal` (loi de Lambert)
+    pub fn illumination(&self, normal: CartCoord) -> f64 {
+        let lambert = normal.normalized().dot(self.direction).max(0.0);
+        self.ambient + self.intensity * lambert
+    }
+
+    // Couleur d'une face : la luminosité et la chroma OKLCH suivent
+    // l'éclairement et la teinte est conservée, ce qui garde des ombres
+    // régulières sans écrêter les couleurs saturées
+    pub fn shade(&self, color: Color, normal: CartCoord) -> Color {
+        let Oklch { l, c, h } = color.to_oklch();
+        let factor = self.illumination(normal);
+        let l = (l * factor).clamp(0.0, 1.0);
+        let c = c * factor.min(1.0);
+
+        Color::from_oklch(Oklch { l, c, h }).with_alpha(color.a)
+    }
+}
+
+// Réglage par défaut : lumière haute venant de la droite de la vue
+// initiale, le dessus est le plus clair et la face gauche la plus sombre
+impl Default for Light {
+    fn default() -> Self {
+        Self::new(CartCoord::new(0.5, 0.25, 1.0), 0.35, 0.7)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_without_direction_is_rejected() {
+        let json =
+            r#"{"direction": {"x": 0.0, "y": 0.0, "z": 0.0}, "intensity": 0.5, "ambient": 0.5}"#;
+        assert!(serde_json::from_str::<Light>(json).is_err());
+    }
+
+    #[test]
+    fn light_is_normalized_on_load() {
+        let json =
+            r#"{"direction": {"x": 0.0, "y": 0.0, "z": 4.0}, "intensity": 3.0, "ambient": -1.0}"#;
+        let light = serde_json::from_str::<Light>(json).unwrap();
+
+        assert_eq!(light.direction, CartCoord::new(0.0, 0.0, 1.0));
+        assert_eq!(light.intensity, 1.0);
+        assert_eq!(light.ambient, 0.0);
+        assert_eq!(light.shadow_opacity, default_shadow_opacity());
+    }
+}
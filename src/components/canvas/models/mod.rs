@@ -0,0 +1,50 @@
+pub mod bsp;
+pub mod color;
+pub mod cuboid;
+pub mod cylinder;
+pub mod decal;
+pub mod depth;
+pub mod dimensions;
+pub mod document;
+pub mod extrusion;
+pub mod fill;
+#[cfg(test)]
+mod fixtures;
+pub mod folder;
+pub mod id;
+pub mod label;
+pub mod light;
+pub mod prism;
+pub mod project;
+pub mod pyramid;
+pub mod shadow;
+pub mod shape;
+pub mod sphere;
+pub mod staircase;
+pub mod style;
+pub mod voxel;
+pub mod wedge;
+
+pub use bsp::*;
+pub use color::*;
+pub use cuboid::*;
+pub use cylinder::*;
+pub use decal::*;
+pub use depth::*;
+pub use dimensions::*;
+pub use document::*;
+pub use extrusion::*;
+pub use fill::*;
+pub use folder::*;
+pub use id::*;
+pub use label::*;
+pub use light::*;
+pub use prism::*;
+pub use pyramid::*;
+pub use shadow::*;
+pub use shape::*;
+pub use sphere::*;
+pub use staircase::*;
+pub use style::*;
+pub use voxel::*;
+pub use wedge::*;
@@ -0,0 +1,104 @@
+use super::document::Document;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// ============================================================================
+// Project File (.isodraw)
+// ============================================================================
+
+// Version courante du format, à incrémenter à chaque changement incompatible.
+// 2 : les dimensions s'écrivent size_x, size_y, size_z (les fichiers en
+// version 1, avec width, height, depth, se lisent toujours)
+pub const PROJECT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectFile {
+    pub version: u32,
+    pub document: Document,
+}
+
+#[derive(Deserialize)]
+struct ProjectHeader {
+    version: u32,
+}
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::Json(err) => write!(f, "invalid project file: {err}"),
+            ProjectError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported project version {version} (expected at most {PROJECT_VERSION})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl From<serde_json::Error> for ProjectError {
+    fn from(err: serde_json::Error) -> Self {
+        ProjectError::Json(err)
+    }
+}
+
+impl Document {
+    pub fn to_json(&self) -> Result<String, ProjectError> {
+        let project = ProjectFile {
+            version: PROJECT_VERSION,
+            document: self.clone(),
+        };
+        Ok(serde_json::to_string_pretty(&project)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Document, ProjectError> {
+        // La version est lue seule d'abord : un fichier plus récent ne
+        // correspond peut-être plus au format du document
+        let header: ProjectHeader = serde_json::from_str(json)?;
+        if header.version > PROJECT_VERSION {
+            return Err(ProjectError::UnsupportedVersion(header.version));
+        }
+
+        let project: ProjectFile = serde_json::from_str(json)?;
+        Ok(project.document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures::cuboid;
+    use super::super::shape::AnyShape;
+    use super::*;
+
+    #[test]
+    fn version_1_dimensions_are_read() {
+        let mut document = Document::new();
+        let shape = cuboid((0.0, 0.0, 0.0), (2.0, 3.0, 4.0));
+        let (id, dimensions) = (shape.id, shape.dimensions);
+        document.add_shape(shape.into());
+
+        // Fichier en version 1 : mêmes tailles, anciens noms de champs
+        let json = document
+            .to_json()
+            .unwrap()
+            .replace(&format!("\"version\": {PROJECT_VERSION}"), "\"version\": 1")
+            .replace("\"size_x\"", "\"width\"")
+            .replace("\"size_y\"", "\"height\"")
+            .replace("\"size_z\"", "\"depth\"");
+        assert!(json.contains("\"width\": 2.0"));
+        assert!(json.contains("\"version\": 1"));
+
+        let loaded = Document::from_json(&json).unwrap();
+        let Some(AnyShape::Cuboid(cuboid)) = loaded.shape(id) else {
+            panic!("cuboid not loaded");
+        };
+        assert_eq!(cuboid.dimensions, dimensions);
+        assert_eq!(loaded, document);
+    }
+}
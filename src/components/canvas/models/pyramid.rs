@@ -0,0 +1,100 @@
+use super::super::frames::{CartCoord, GridCoord};
+use super::color::Color;
+use super::dimensions::Dimensions;
+use super::id::NodeId;
+use super::shape::{Bounds, Face, Shape, Turn};
+use super::style::ShapeStyle;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Pyramid
+// ============================================================================
+
+// Pyramide à base rectangulaire, sommet à la verticale du centre de la base
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pyramid {
+    pub id: NodeId,
+    pub position: GridCoord,
+    pub dimensions: Dimensions,
+    pub style: ShapeStyle,
+}
+
+impl Pyramid {
+    pub fn new(id: NodeId, position: GridCoord, dimensions: Dimensions, color: Color) -> Self {
+        Self {
+            id,
+            position,
+            dimensions,
+            style: ShapeStyle {
+                color,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn with_style(mut self, style: ShapeStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Shape for Pyramid {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.id = id;
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // La base et quatre triangles, orientés par leur normale
+    fn faces(&self) -> Vec<Face> {
+        let Bounds { min, max } = self.bounds();
+
+        let b00 = CartCoord::new(min.x, min.y, min.z);
+        let b10 = CartCoord::new(max.x, min.y, min.z);
+        let b11 = CartCoord::new(max.x, max.y, min.z);
+        let b01 = CartCoord::new(min.x, max.y, min.z);
+        let apex = CartCoord::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0, max.z);
+
+        vec![
+            Face::from_vertices(vec![b00, b01, b11, b10]),
+            Face::from_vertices(vec![b00, b10, apex]),
+            Face::from_vertices(vec![b10, b11, apex]),
+            Face::from_vertices(vec![b11, b01, apex]),
+            Face::from_vertices(vec![b01, b00, apex]),
+        ]
+    }
+
+    fn bounds(&self) -> Bounds {
+        let dims = self.dimensions.with_default(1.0);
+        let min: CartCoord = self.position.into();
+
+        Bounds::new(
+            min,
+            CartCoord::new(
+                min.x + dims.size_x,
+                min.y + dims.size_y,
+                min.z + dims.size_z,
+            ),
+        )
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.position = self.position + offset;
+    }
+
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        self.position = self.bounds().turned(turn, pivot).min.into();
+        self.dimensions = self.dimensions.turned();
+        self.style.turn(turn);
+    }
+}
@@ -0,0 +1,418 @@
+use super::super::frames::CartCoord;
+use super::id::NodeId;
+use super::light::Light;
+use super::shape::{Face, Shape};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// ============================================================================
+// Shadows
+// ============================================================================
+
+// Ombre portée sur un plan horizontal, en coordonnées du monde. Les ombres
+// d'un même récepteur se chevauchent : elles sont dessinées opaques dans un
+// groupe translucide pour ne pas s'assombrir mutuellement
+pub type ShadowPolygon = Vec<CartCoord>;
+
+const EPSILON: f64 = 1e-9;
+
+// Hauteur minimale de la lumière au-dessus de l'horizon pour projeter des
+// ombres de longueur raisonnable
+const MIN_LIGHT_HEIGHT: f64 = 0.05;
+
+// Taille en cases des carrés de l'index des ombres d'un même plan
+const BUCKET_SIZE: f64 = 4.0;
+
+// Rectangle (x min, y min, x max, y max) dans le plan horizontal
+type Extent = (f64, f64, f64, f64);
+
+// Faces éclairées de chaque forme du document, relevées une seule fois pour
+// tous les calques. Les ombres projetées sur un plan sont gardées pour toutes
+// les faces qui se trouvent à sa hauteur
+pub struct ShadowCasters {
+    direction: CartCoord,
+    casters: Vec<Caster>,
+    levels: RefCell<HashMap<u64, Rc<ShadowLevel>>>,
+}
+
+#[derive(PartialEq)]
+struct Caster {
+    id: NodeId,
+    // Altitude du haut de la forme : elle ne fait pas d'ombre plus haut
+    top: f64,
+    faces: Vec<Vec<CartCoord>>,
+}
+
+// Ombres sur un plan horizontal avec la forme qui les projette, rangées par
+// carrés de la grille pour ne découper que celles proches d'une face
+struct ShadowLevel {
+    shadows: Vec<(NodeId, Extent, ShadowPolygon)>,
+    buckets: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl ShadowCasters {
+    pub fn new<S: Shape>(shapes: &[S], light: &Light) -> Self {
+        let direction = light.direction;
+        let casters = if casts_shadows(light) {
+            shapes
+                .iter()
+                .map(|shape| Caster {
+                    id: shape.id(),
+                    top: shape.bounds().max.z,
+                    faces: shape
+                        .shadow_faces()
+                        .into_iter()
+                        .filter(|face| face.normal().dot(direction) > EPSILON)
+                        .map(|face| face.vertices)
+                        .collect(),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            direction,
+            casters,
+            levels: RefCell::new(HashMap::new()),
+        }
+    }
+
+    // Silhouette de la partie de la forme `index` au-dessus de `height`,
+    // projetée sur le plan z = height : union des projections de ses faces
+    // éclairées
+    fn silhouette(&self, index: usize, height: f64) -> impl Iterator<Item = ShadowPolygon> + '_ {
+        let direction = self.direction;
+
+        self.casters[index]
+            .faces
+            .iter()
+            .map(move |face| clip_above(face, height))
+            .filter(|polygon| polygon.len() >= 3)
+            .map(move |polygon| {
+                polygon
+                    .into_iter()
+                    .map(|p| p - direction * ((p.z - height) / direction.z))
+                    .collect::<ShadowPolygon>()
+            })
+            .filter(|polygon| signed_area(polygon).abs() > EPSILON)
+    }
+
+    // Ombres de toutes les formes sur le plan z = height
+    fn level(&self, height: f64) -> Rc<ShadowLevel> {
+        let cached = self.levels.borrow().get(&height.to_bits()).cloned();
+        if let Some(level) = cached {
+            return level;
+        }
+
+        let shadows = (0..self.casters.len())
+            .filter(|&index| self.casters[index].top > height + EPSILON)
+            .flat_map(|index| {
+                let id = self.casters[index].id;
+                self.silhouette(index, height)
+                    .map(move |shadow| (id, extent(&shadow), shadow))
+            })
+            .collect();
+        let level = Rc::new(ShadowLevel::new(shadows));
+        self.levels
+            .borrow_mut()
+            .insert(height.to_bits(), level.clone());
+        level
+    }
+}
+
+// Les mêmes faces donnent les mêmes ombres : les plans déjà calculés
+// n'entrent pas dans la comparaison
+impl PartialEq for ShadowCasters {
+    fn eq(&self, other: &Self) -> bool {
+        self.direction == other.direction && self.casters == other.casters
+    }
+}
+
+impl ShadowLevel {
+    fn new(shadows: Vec<(NodeId, Extent, ShadowPolygon)>) -> Self {
+        let mut buckets: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (index, (_, extent, _)) in shadows.iter().enumerate() {
+            for key in bucket_keys(*extent) {
+                buckets.entry(key).or_default().push(index);
+            }
+        }
+        Self { shadows, buckets }
+    }
+
+    // Ombres qui touchent `area`, sauf celles de la forme `receiver`
+    fn near(&self, area: Extent, receiver: NodeId) -> impl Iterator<Item = &ShadowPolygon> {
+        let mut indices: Vec<usize> = bucket_keys(area)
+            .filter_map(|key| self.buckets.get(&key))
+            .flatten()
+            .copied()
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+
+        indices
+            .into_iter()
+            .map(|index| &self.shadows[index])
+            .filter(move |(caster, extent, _)| *caster != receiver && overlaps(*extent, area))
+            .map(|(_, _, shadow)| shadow)
+    }
+}
+
+// Carrés de l'index couverts par un rectangle
+fn bucket_keys((x0, y0, x1, y1): Extent) -> impl Iterator<Item = (i32, i32)> {
+    let cell = |value: f64| (value / BUCKET_SIZE).floor() as i32;
+    let (i0, i1, j0, j1) = (cell(x0), cell(x1), cell(y0), cell(y1));
+    (i0..=i1).flat_map(move |i| (j0..=j1).map(move |j| (i, j)))
+}
+
+// Ombres de toutes les formes sur le sol (z = 0)
+pub fn ground_shadows(casters: &ShadowCasters) -> Vec<ShadowPolygon> {
+    (0..casters.casters.len())
+        .flat_map(|index| casters.silhouette(index, 0.0))
+        .collect()
+}
+
+// Ombres reçues par une face horizontale tournée vers le haut de la forme
+// `receiver`, découpées au contour de la face
+pub fn face_shadows(face: &Face, casters: &ShadowCasters, receiver: NodeId) -> Vec<ShadowPolygon> {
+    if casters.casters.is_empty() || !is_upward(face) {
+        return Vec::new();
+    }
+
+    let level = casters.level(face.vertices[0].z);
+    let pieces = convex_pieces(&face.vertices);
+
+    level
+        .near(extent(&face.vertices), receiver)
+        .flat_map(|shadow| {
+            pieces
+                .iter()
+                .map(move |piece| clip_to_convex(shadow, piece))
+        })
+        .filter(|shadow| shadow.len() >= 3)
+        .collect()
+}
+
+// Ombres reçues par toutes les faces du dessus de `shape`
+pub fn received_shadows<S: Shape>(shape: &S, casters: &ShadowCasters) -> Vec<ShadowPolygon> {
+    if casters.casters.is_empty() {
+        return Vec::new();
+    }
+
+    shape
+        .shadow_faces()
+        .iter()
+        .flat_map(|face| face_shadows(face, casters, shape.id()))
+        .collect()
+}
+
+fn casts_shadows(light: &Light) -> bool {
+    light.cast_shadows && light.direction.z > MIN_LIGHT_HEIGHT
+}
+
+// Face horizontale dont la normale pointe vers le haut
+fn is_upward(face: &Face) -> bool {
+    let height = face.vertices.first().map_or(0.0, |v| v.z);
+    face.vertices.len() >= 3
+        && face.normal().z > EPSILON
+        && face.vertices.iter().all(|v| (v.z - height).abs() < EPSILON)
+}
+
+// Rectangle qui contient le polygone
+fn extent(polygon: &[CartCoord]) -> Extent {
+    polygon.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(x0, y0, x1, y1), p| (x0.min(p.x), y0.min(p.y), x1.max(p.x), y1.max(p.y)),
+    )
+}
+
+fn overlaps(a: Extent, b: Extent) -> bool {
+    a.0 < b.2 - EPSILON && b.0 < a.2 - EPSILON && a.1 < b.3 - EPSILON && b.1 < a.3 - EPSILON
+}
+
+// Partie d'un polygone située au-dessus du plan z = height
+fn clip_above(polygon: &[CartCoord], height: f64) -> Vec<CartCoord> {
+    let count = polygon.len();
+    let mut out = Vec::with_capacity(count + 1);
+
+    for i in 0..count {
+        let (a, b) = (polygon[i], polygon[(i + 1) % count]);
+        let (da, db) = (a.z - height, b.z - height);
+
+        if da >= -EPSILON {
+            out.push(a);
+        }
+        if (da > EPSILON && db < -EPSILON) || (da < -EPSILON && db > EPSILON) {
+            out.push(a + (b - a) * (da / (da - db)));
+        }
+    }
+    out
+}
+
+// Aire signée du polygone projeté sur le plan horizontal (positive dans le
+// sens direct)
+fn signed_area(polygon: &[CartCoord]) -> f64 {
+    let count = polygon.len();
+    (0..count)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % count]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+// Morceaux convexes d'un polygone horizontal : le polygone lui-même s'il est
+// convexe, sinon ses triangles (découpage par oreilles)
+fn convex_pieces(polygon: &[CartCoord]) -> Vec<Vec<CartCoord>> {
+    let orientation = signed_area(polygon).signum();
+    let turn = |a: CartCoord, b: CartCoord, c: CartCoord| {
+        orientation * ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x))
+    };
+
+    let count = polygon.len();
+    if (0..count).all(|i| {
+        turn(
+            polygon[i],
+            polygon[(i + 1) % count],
+            polygon[(i + 2) % count],
+        ) >= -EPSILON
+    }) {
+        return vec![polygon.to_vec()];
+    }
+
+    let mut remaining = polygon.to_vec();
+    let mut triangles = Vec::new();
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corners = |i: usize| {
+            (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            )
+        };
+
+        // Oreille : sommet convexe dont le triangle ne contient aucun autre sommet
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = corners(i);
+            turn(a, b, c) > EPSILON
+                && remaining.iter().enumerate().all(|(j, &p)| {
+                    j == i
+                        || j == (i + n - 1) % n
+                        || j == (i + 1) % n
+                        || turn(a, b, p) < 0.0
+                        || turn(b, c, p) < 0.0
+                        || turn(c, a, p) < 0.0
+                })
+        });
+
+        // Polygone dégénéré (côtés croisés) : les triangles déjà trouvés suffisent
+        let Some(i) = ear else {
+            return triangles;
+        };
+        let (a, b, c) = corners(i);
+        triangles.push(vec![a, b, c]);
+        remaining.remove(i);
+    }
+    triangles.push(remaining);
+    triangles
+}
+
+// Découpe (Sutherland-Hodgman) d'un polygone par un polygone convexe, dans
+// le plan horizontal ; les sommets gardent l'altitude du polygone découpé
+fn clip_to_convex(subject: &[CartCoord], clip: &[CartCoord]) -> Vec<CartCoord> {
+    let orientation = signed_area(clip).signum();
+    let count = clip.len();
+    let mut output = subject.to_vec();
+
+    for i in 0..count {
+        if output.is_empty() {
+            break;
+        }
+
+        let (a, b) = (clip[i], clip[(i + 1) % count]);
+        // Distance (signée selon l'orientation) à gauche de l'arête a -> b
+        let side =
+            |p: CartCoord| orientation * ((b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x));
+
+        let input = std::mem::take(&mut output);
+        let n = input.len();
+        for j in 0..n {
+            let (p, q) = (input[j], input[(j + 1) % n]);
+            let (sp, sq) = (side(p), side(q));
+
+            if sp >= 0.0 {
+                output.push(p);
+            }
+            if (sp >= 0.0) != (sq >= 0.0) {
+                output.push(p + (q - p) * (sp / (sp - sq)));
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::frames::GridCoord;
+    use super::super::color::Color;
+    use super::super::cuboid::Cuboid;
+    use super::super::dimensions::Dimensions;
+    use super::super::id::NodeId;
+    use super::super::shape::AnyShape;
+    use super::super::voxel::VoxelModel;
+    use super::*;
+
+    fn scene() -> (Vec<AnyShape>, Light) {
+        let mut slab = VoxelModel::new(NodeId::new(), GridCoord::new(0.0, 0.0, 0.0));
+        for x in 0..10 {
+            for y in 0..10 {
+                slab.voxels.insert((x, y, 0), Color::WHITE);
+            }
+        }
+        let tower = Cuboid::new(
+            NodeId::new(),
+            GridCoord::new(4.0, 4.0, 1.0),
+            Dimensions::new(1.0, 1.0, 3.0),
+            Color::WHITE,
+        );
+        let light = Light {
+            cast_shadows: true,
+            ..Light::default()
+        };
+        (vec![slab.into(), tower.into()], light)
+    }
+
+    #[test]
+    fn voxel_faces_are_merged_before_casting() {
+        let (shapes, light) = scene();
+        let casters = ShadowCasters::new(&shapes[..1], &light);
+
+        // Dessus et deux côtés éclairés, au lieu d'une ombre par face de cube
+        assert_eq!(ground_shadows(&casters).len(), 3);
+    }
+
+    #[test]
+    fn shadows_fall_on_the_top_of_other_shapes() {
+        let (shapes, light) = scene();
+        let casters = ShadowCasters::new(&shapes, &light);
+
+        let received = received_shadows(&shapes[0], &casters);
+        assert!(!received.is_empty());
+        for point in received.iter().flatten() {
+            assert!((point.z - 1.0).abs() < EPSILON);
+            assert!((-EPSILON..=10.0 + EPSILON).contains(&point.x));
+            assert!((-EPSILON..=10.0 + EPSILON).contains(&point.y));
+        }
+
+        // La tour ne reçoit pas sa propre ombre
+        assert!(received_shadows(&shapes[1], &casters).is_empty());
+    }
+}
@@ -0,0 +1,759 @@
+use super::super::frames::{Camera, CartCoord, GridCoord, IsoCoord, View};
+use super::cuboid::Cuboid;
+use super::cylinder::Cylinder;
+use super::depth::face_order;
+use super::extrusion::Extrusion;
+use super::id::NodeId;
+use super::prism::Prism;
+use super::pyramid::Pyramid;
+use super::sphere::Sphere;
+use super::staircase::Staircase;
+use super::style::ShapeStyle;
+use super::voxel::VoxelModel;
+use super::wedge::Wedge;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Faces
+// ============================================================================
+
+// Orientation d'une face. Dans la vue par défaut, Top, Right (+x) et
+// Left (+y) sont visibles ; Bottom, BackLeft (-x) et BackRight (-y) sont cachées.
+// Curved désigne les facettes d'une surface courbe (flanc d'un cylindre,
+// sphère), stylées et dessinées d'un seul tenant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FaceType {
+    Top,
+    Right,
+    Left,
+    Bottom,
+    BackLeft,
+    BackRight,
+    Curved,
+}
+
+impl FaceType {
+    // Orientation la plus proche d'une normale
+    pub fn from_normal(normal: CartCoord) -> Self {
+        let (x, y, z) = (normal.x.abs(), normal.y.abs(), normal.z.abs());
+
+        if z >= x && z >= y {
+            if normal.z >= 0.0 {
+                FaceType::Top
+            } else {
+                FaceType::Bottom
+            }
+        } else if x >= y {
+            if normal.x >= 0.0 {
+                FaceType::Right
+            } else {
+                FaceType::BackLeft
+            }
+        } else if normal.y >= 0.0 {
+            FaceType::Left
+        } else {
+            FaceType::BackRight
+        }
+    }
+
+    // Orientation de la face une fois la forme tournée d'un quart de tour
+    pub fn turned(self, turn: Turn) -> Self {
+        let sides = [
+            FaceType::Right,
+            FaceType::Left,
+            FaceType::BackLeft,
+            FaceType::BackRight,
+        ];
+        match sides.iter().position(|&side| side == self) {
+            Some(index) => sides[(index + turn.steps()) % 4],
+            None => self,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Face {
+    pub face_type: FaceType,
+    pub vertices: Vec<CartCoord>,
+}
+
+impl Face {
+    pub fn new(face_type: FaceType, vertices: Vec<CartCoord>) -> Self {
+        Self {
+            face_type,
+            vertices,
+        }
+    }
+
+    // Face inclinée : l'orientation est celle de la plus proche de sa normale
+    pub fn from_vertices(vertices: Vec<CartCoord>) -> Self {
+        let face = Self::new(FaceType::Top, vertices);
+        Self {
+            face_type: FaceType::from_normal(face.normal()),
+            ..face
+        }
+    }
+
+    // Normale sortante (non normalisée) : somme des produits vectoriels
+    // des sommets consécutifs, valable pour tout polygone plan
+    pub fn normal(&self) -> CartCoord {
+        let count = self.vertices.len();
+
+        (0..count).fold(CartCoord::new(0.0, 0.0, 0.0), |normal, i| {
+            normal + self.vertices[i].cross(self.vertices[(i + 1) % count])
+        })
+    }
+
+    // Une face est visible si sa normale pointe vers l'observateur
+    pub fn is_visible(&self, camera: &Camera) -> bool {
+        self.normal().dot(camera.view_direction()) > 0.0
+    }
+
+    // Repère de la surface de la face
+    pub fn frame(&self) -> SurfaceFrame {
+        let normal = self.normal().normalized();
+        let up = CartCoord::new(0.0, 0.0, 1.0);
+
+        // Faces horizontales : axes du monde, pour que les motifs se
+        // raccordent d'une forme à l'autre
+        let (u, v) = if normal.z.abs() > 0.999 {
+            let v = CartCoord::new(0.0, normal.z.signum(), 0.0);
+            (CartCoord::new(1.0, 0.0, 0.0), v)
+        } else {
+            let u = normal.cross(up).normalized();
+            (u, normal.cross(u))
+        };
+
+        let origin = self
+            .vertices
+            .first()
+            .map_or(CartCoord::new(0.0, 0.0, 0.0), |&p| normal * normal.dot(p));
+
+        SurfaceFrame { origin, u, v }
+    }
+
+    // Repère d'une face horizontale tourné pour que u suive `direction` :
+    // une image ou un texte posé dessus tourne avec sa forme. Les faces
+    // verticales gardent leur repère
+    pub fn oriented_frame(&self, direction: Direction) -> SurfaceFrame {
+        let frame = self.frame();
+        if !matches!(self.face_type, FaceType::Top | FaceType::Bottom) {
+            return frame;
+        }
+
+        let CartCoord { x: cos, y: sin, .. } = direction.unit();
+        let turn = |p: CartCoord| CartCoord::new(cos * p.x - sin * p.y, sin * p.x + cos * p.y, p.z);
+        SurfaceFrame {
+            u: turn(frame.u),
+            v: turn(frame.v),
+            ..frame
+        }
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        let first = self
+            .vertices
+            .first()
+            .copied()
+            .unwrap_or(CartCoord::new(0.0, 0.0, 0.0));
+
+        self.vertices
+            .iter()
+            .fold(Bounds::new(first, first), |b, v| {
+                Bounds::new(
+                    CartCoord::new(b.min.x.min(v.x), b.min.y.min(v.y), b.min.z.min(v.z)),
+                    CartCoord::new(b.max.x.max(v.x), b.max.y.max(v.y), b.max.z.max(v.z)),
+                )
+            })
+    }
+
+    pub fn iso_vertices(&self, camera: &Camera) -> Vec<IsoCoord> {
+        self.vertices.iter().map(|&v| camera.project(v)).collect()
+    }
+
+    pub fn contains(&self, point: IsoCoord, camera: &Camera) -> bool {
+        point_in_polygon(point, &self.iso_vertices(camera))
+    }
+}
+
+// Contour d'un groupe de faces jointives (surface courbe) : arêtes qui
+// n'appartiennent qu'à une seule face du groupe, dans le sens des faces
+pub fn outline_edges(faces: &[Face]) -> Vec<(CartCoord, CartCoord)> {
+    let edges: Vec<(CartCoord, CartCoord)> = faces
+        .iter()
+        .flat_map(|face| {
+            let count = face.vertices.len();
+            (0..count).map(move |i| (face.vertices[i], face.vertices[(i + 1) % count]))
+        })
+        .collect();
+
+    // Une arête intérieure est parcourue une fois dans chaque sens
+    edges
+        .iter()
+        .filter(|&&(a, b)| {
+            !edges
+                .iter()
+                .any(|&(c, d)| same_point(a, d) && same_point(b, c))
+        })
+        .copied()
+        .collect()
+}
+
+// Arêtes de contour mises bout à bout en un polygone fermé
+pub fn outline_polygon(edges: &[(CartCoord, CartCoord)]) -> Vec<CartCoord> {
+    let Some(&(start, mut current)) = edges.first() else {
+        return Vec::new();
+    };
+
+    let mut polygon = vec![start];
+    while !same_point(current, start) && polygon.len() <= edges.len() {
+        polygon.push(current);
+        match edges.iter().find(|&&(a, _)| same_point(a, current)) {
+            Some(&(_, next)) => current = next,
+            None => break,
+        }
+    }
+    polygon
+}
+
+fn same_point(a: CartCoord, b: CartCoord) -> bool {
+    (a - b).length() < 1e-9
+}
+
+// Test pair/impair : le point est dedans si un rayon horizontal croise
+// un nombre impair d'arêtes du polygone
+fn point_in_polygon(point: IsoCoord, polygon: &[IsoCoord]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+
+    inside
+}
+
+// ============================================================================
+// Direction
+// ============================================================================
+
+// Orientation horizontale d'une forme asymétrique (montée d'un coin, faîtage
+// d'un prisme). Les formes sont décrites dans un repère local où `a` suit la
+// direction et `b` est en travers, puis placées dans leur empreinte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Direction {
+    #[default]
+    PlusX,
+    PlusY,
+    MinusX,
+    MinusY,
+}
+
+impl Direction {
+    // Longueur le long de la direction et largeur en travers d'une empreinte
+    // (taille en x, taille en y)
+    pub fn extent(self, size_x: f64, size_y: f64) -> (f64, f64) {
+        match self {
+            Direction::PlusX | Direction::MinusX => (size_x, size_y),
+            Direction::PlusY | Direction::MinusY => (size_y, size_x),
+        }
+    }
+
+    // Point local (a, b, z) dans l'empreinte qui part de `origin`. Le
+    // passage est une rotation : le sens des faces est conservé
+    pub fn place(
+        self,
+        origin: CartCoord,
+        size_x: f64,
+        size_y: f64,
+        a: f64,
+        b: f64,
+        z: f64,
+    ) -> CartCoord {
+        let (x, y) = match self {
+            Direction::PlusX => (a, b),
+            Direction::PlusY => (size_x - b, a),
+            Direction::MinusX => (size_x - a, size_y - b),
+            Direction::MinusY => (b, size_y - a),
+        };
+        origin + CartCoord::new(x, y, z)
+    }
+
+    // Vecteur unitaire horizontal dans le sens de la direction
+    pub fn unit(self) -> CartCoord {
+        match self {
+            Direction::PlusX => CartCoord::new(1.0, 0.0, 0.0),
+            Direction::PlusY => CartCoord::new(0.0, 1.0, 0.0),
+            Direction::MinusX => CartCoord::new(-1.0, 0.0, 0.0),
+            Direction::MinusY => CartCoord::new(0.0, -1.0, 0.0),
+        }
+    }
+
+    pub fn turned(self, turn: Turn) -> Self {
+        let directions = [
+            Direction::PlusX,
+            Direction::PlusY,
+            Direction::MinusX,
+            Direction::MinusY,
+        ];
+        let index = directions.iter().position(|&d| d == self).unwrap_or(0);
+        directions[(index + turn.steps()) % 4]
+    }
+}
+
+// ============================================================================
+// Turn
+// ============================================================================
+
+// Quart de tour autour de l'axe vertical. Dans le sens horaire, x passe
+// sur y : la forme tourne à l'écran dans le même sens que la vue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl Turn {
+    // Nombre de quarts de tour dans le sens x -> y
+    fn steps(self) -> usize {
+        match self {
+            Turn::Clockwise => 1,
+            Turn::CounterClockwise => 3,
+        }
+    }
+
+    // Point tourné autour de l'axe vertical qui passe par `pivot`
+    pub fn apply(self, point: CartCoord, pivot: CartCoord) -> CartCoord {
+        let (dx, dy) = (point.x - pivot.x, point.y - pivot.y);
+        let (x, y) = match self {
+            Turn::Clockwise => (-dy, dx),
+            Turn::CounterClockwise => (dy, -dx),
+        };
+        CartCoord::new(pivot.x + x, pivot.y + y, point.z)
+    }
+}
+
+// ============================================================================
+// Surface Frame
+// ============================================================================
+
+// Repère plan d'une face pour y plaquer motifs, textes et images, en cases :
+// l'origine est le point du plan le plus proche de l'origine du monde, u est
+// horizontal (de gauche à droite vu de face) et v descend le long de la face
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceFrame {
+    pub origin: CartCoord,
+    pub u: CartCoord,
+    pub v: CartCoord,
+}
+
+impl SurfaceFrame {
+    // Coordonnées (u, v) d'un point du plan
+    pub fn local(&self, point: CartCoord) -> (f64, f64) {
+        let offset = point - self.origin;
+        (offset.dot(self.u), offset.dot(self.v))
+    }
+
+    // Point du monde de coordonnées (u, v)
+    pub fn world(&self, u: f64, v: f64) -> CartCoord {
+        self.origin + self.u * u + self.v * v
+    }
+
+    // Transformation SVG du repère de la face vers l'écran (la projection
+    // est linéaire, l'image du repère suffit)
+    pub fn svg_transform(&self, camera: &Camera) -> String {
+        let origin = camera.project(self.origin);
+        let u = camera.project(self.u);
+        let v = camera.project(self.v);
+
+        format!(
+            "matrix({} {} {} {} {} {})",
+            u.x, u.y, v.x, v.y, origin.x, origin.y
+        )
+    }
+}
+
+// ============================================================================
+// Bounds
+// ============================================================================
+
+// Boîte englobante alignée sur les axes, en coordonnées cartésiennes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: CartCoord,
+    pub max: CartCoord,
+}
+
+impl Bounds {
+    pub fn new(min: CartCoord, max: CartCoord) -> Self {
+        Self { min, max }
+    }
+
+    // Centre de l'empreinte au sol, à la base de la boîte
+    pub fn footprint_center(&self) -> CartCoord {
+        CartCoord::new(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+            self.min.z,
+        )
+    }
+
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        Bounds::new(
+            CartCoord::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            CartCoord::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        )
+    }
+
+    // Boîte de la forme tournée d'un quart de tour autour de `pivot`
+    pub fn turned(&self, turn: Turn, pivot: CartCoord) -> Bounds {
+        let (a, b) = (turn.apply(self.min, pivot), turn.apply(self.max, pivot));
+        Bounds::new(
+            CartCoord::new(a.x.min(b.x), a.y.min(b.y), self.min.z),
+            CartCoord::new(a.x.max(b.x), a.y.max(b.y), self.max.z),
+        )
+    }
+
+    pub fn corners(&self) -> [CartCoord; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            CartCoord::new(min.x, min.y, min.z),
+            CartCoord::new(max.x, min.y, min.z),
+            CartCoord::new(max.x, max.y, min.z),
+            CartCoord::new(min.x, max.y, min.z),
+            CartCoord::new(min.x, min.y, max.z),
+            CartCoord::new(max.x, min.y, max.z),
+            CartCoord::new(max.x, max.y, max.z),
+            CartCoord::new(min.x, max.y, max.z),
+        ]
+    }
+
+    // Boîte englobante dans le repère de la vue
+    pub fn in_view(&self, view: &View) -> Bounds {
+        let corners = self.corners().map(|corner| view.apply(corner));
+        let min = corners.iter().fold(corners[0], |min, c| {
+            CartCoord::new(min.x.min(c.x), min.y.min(c.y), min.z.min(c.z))
+        });
+        let max = corners.iter().fold(corners[0], |max, c| {
+            CartCoord::new(max.x.max(c.x), max.y.max(c.y), max.z.max(c.z))
+        });
+        Bounds::new(min, max)
+    }
+
+    // Rectangle (min, max) occupé à l'écran par la projection de la boîte
+    pub fn iso_extent(&self, camera: &Camera) -> (IsoCoord, IsoCoord) {
+        self.corners().iter().fold(
+            (
+                IsoCoord::new(f64::INFINITY, f64::INFINITY),
+                IsoCoord::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), &corner| {
+                let iso = camera.project(corner);
+                (
+                    IsoCoord::new(min.x.min(iso.x), min.y.min(iso.y)),
+                    IsoCoord::new(max.x.max(iso.x), max.y.max(iso.y)),
+                )
+            },
+        )
+    }
+}
+
+// ============================================================================
+// Shape
+// ============================================================================
+
+pub trait Shape {
+    fn id(&self) -> NodeId;
+
+    fn set_id(&mut self, id: NodeId);
+
+    fn style(&self) -> &ShapeStyle;
+
+    fn style_mut(&mut self) -> &mut ShapeStyle;
+
+    // Toutes les faces, normales vers l'extérieur
+    fn faces(&self) -> Vec<Face>;
+
+    // Faces tournées vers l'observateur
+    fn visible_faces(&self, camera: &Camera) -> Vec<Face> {
+        self.faces()
+            .into_iter()
+            .filter(|face| face.is_visible(camera))
+            .collect()
+    }
+
+    fn bounds(&self) -> Bounds;
+
+    // Déplacement en cases de la grille
+    fn translate(&mut self, offset: GridCoord);
+
+    // Quart de tour autour de l'axe vertical qui passe par `pivot` ; les
+    // surcharges de style suivent leurs faces
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord);
+
+    fn contains(&self, point: IsoCoord, camera: &Camera) -> bool {
+        self.faces().iter().any(|face| face.contains(point, camera))
+    }
+
+    // Une forme non convexe (contour en L ou en U) peut se cacher
+    // elle-même : ses faces sont alors triées avant d'être dessinées
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    // Faces planes d'une forme non convexe, de l'arrière vers l'avant
+    fn sort_faces(&self, faces: Vec<Face>, camera: &Camera) -> Vec<Face> {
+        face_order(&faces, camera)
+            .into_iter()
+            .map(|index| faces[index].clone())
+            .collect()
+    }
+
+    // Faces planes visibles dans l'ordre où elles sont dessinées. Une forme
+    // peut dessiner moins de faces qu'elle n'en a, en fusionnant les faces
+    // voisines
+    fn drawn_faces(&self, camera: &Camera) -> Vec<Face> {
+        let flat: Vec<Face> = self
+            .visible_faces(camera)
+            .into_iter()
+            .filter(|face| face.face_type != FaceType::Curved)
+            .collect();
+
+        if self.is_convex() {
+            flat
+        } else {
+            self.sort_faces(flat, camera)
+        }
+    }
+
+    // Style effectif d'une face ; une forme multicolore le déduit de la
+    // position de la face
+    fn face_style(&self, face: &Face) -> ShapeStyle {
+        self.style().for_face(face.face_type)
+    }
+
+    // Les surcharges de style sont rangées par type de face : une face ne peut
+    // être peinte seule que si aucune autre face de la forme n'a son type. Les
+    // facettes d'une surface courbe forment une seule face
+    fn has_own_style(&self, face_type: FaceType) -> bool {
+        face_type == FaceType::Curved
+            || self
+                .faces()
+                .iter()
+                .filter(|face| face.face_type == face_type)
+                .count()
+                <= 1
+    }
+
+    // Faces qui projettent et reçoivent les ombres : celles de la forme, ou
+    // moins de faces qui couvrent la même surface
+    fn shadow_faces(&self) -> Vec<Face> {
+        self.faces()
+    }
+
+    // Forme de la surface courbe (facettes Curved), pour son dégradé
+    fn curvature(&self) -> Option<Curvature> {
+        None
+    }
+}
+
+// Les facettes d'une surface courbe sont dessinées en un seul polygone, avec
+// un dégradé qui suit la variation de la normale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curvature {
+    // Surface de révolution à axe vertical : la normale ne tourne qu'autour
+    // de l'axe (dégradé horizontal)
+    Vertical,
+    // Portion de sphère (dégradé radial depuis le point le plus éclairé)
+    Spherical { center: CartCoord, radius: f64 },
+}
+
+// ============================================================================
+// AnyShape
+// ============================================================================
+
+// Enveloppe de toutes les primitives, pour les stocker dans une même liste
+// et les passer en props aux composants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AnyShape {
+    Cuboid(Cuboid),
+    Cylinder(Cylinder),
+    Sphere(Sphere),
+    Pyramid(Pyramid),
+    Wedge(Wedge),
+    Prism(Prism),
+    Extrusion(Extrusion),
+    Staircase(Staircase),
+    Voxels(VoxelModel),
+}
+
+impl AnyShape {
+    fn as_shape(&self) -> &dyn Shape {
+        match self {
+            AnyShape::Cuboid(cuboid) => cuboid,
+            AnyShape::Cylinder(cylinder) => cylinder,
+            AnyShape::Sphere(sphere) => sphere,
+            AnyShape::Pyramid(pyramid) => pyramid,
+            AnyShape::Wedge(wedge) => wedge,
+            AnyShape::Prism(prism) => prism,
+            AnyShape::Extrusion(extrusion) => extrusion,
+            AnyShape::Staircase(staircase) => staircase,
+            AnyShape::Voxels(model) => model,
+        }
+    }
+
+    fn as_shape_mut(&mut self) -> &mut dyn Shape {
+        match self {
+            AnyShape::Cuboid(cuboid) => cuboid,
+            AnyShape::Cylinder(cylinder) => cylinder,
+            AnyShape::Sphere(sphere) => sphere,
+            AnyShape::Pyramid(pyramid) => pyramid,
+            AnyShape::Wedge(wedge) => wedge,
+            AnyShape::Prism(prism) => prism,
+            AnyShape::Extrusion(extrusion) => extrusion,
+            AnyShape::Staircase(staircase) => staircase,
+            AnyShape::Voxels(model) => model,
+        }
+    }
+
+    pub fn translated(&self, offset: GridCoord) -> Self {
+        let mut shape = self.clone();
+        shape.translate(offset);
+        shape
+    }
+}
+
+impl From<Cuboid> for AnyShape {
+    fn from(cuboid: Cuboid) -> Self {
+        AnyShape::Cuboid(cuboid)
+    }
+}
+
+impl From<Cylinder> for AnyShape {
+    fn from(cylinder: Cylinder) -> Self {
+        AnyShape::Cylinder(cylinder)
+    }
+}
+
+impl From<Sphere> for AnyShape {
+    fn from(sphere: Sphere) -> Self {
+        AnyShape::Sphere(sphere)
+    }
+}
+
+impl From<Pyramid> for AnyShape {
+    fn from(pyramid: Pyramid) -> Self {
+        AnyShape::Pyramid(pyramid)
+    }
+}
+
+impl From<Wedge> for AnyShape {
+    fn from(wedge: Wedge) -> Self {
+        AnyShape::Wedge(wedge)
+    }
+}
+
+impl From<Prism> for AnyShape {
+    fn from(prism: Prism) -> Self {
+        AnyShape::Prism(prism)
+    }
+}
+
+impl From<Extrusion> for AnyShape {
+    fn from(extrusion: Extrusion) -> Self {
+        AnyShape::Extrusion(extrusion)
+    }
+}
+
+impl From<Staircase> for AnyShape {
+    fn from(staircase: Staircase) -> Self {
+        AnyShape::Staircase(staircase)
+    }
+}
+
+impl From<VoxelModel> for AnyShape {
+    fn from(model: VoxelModel) -> Self {
+        AnyShape::Voxels(model)
+    }
+}
+
+impl Shape for AnyShape {
+    fn id(&self) -> NodeId {
+        self.as_shape().id()
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.as_shape_mut().set_id(id)
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        self.as_shape().style()
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        self.as_shape_mut().style_mut()
+    }
+
+    fn faces(&self) -> Vec<Face> {
+        self.as_shape().faces()
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.as_shape().bounds()
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.as_shape_mut().translate(offset)
+    }
+
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        self.as_shape_mut().rotate(turn, pivot)
+    }
+
+    fn contains(&self, point: IsoCoord, camera: &Camera) -> bool {
+        self.as_shape().contains(point, camera)
+    }
+
+    fn is_convex(&self) -> bool {
+        self.as_shape().is_convex()
+    }
+
+    fn sort_faces(&self, faces: Vec<Face>, camera: &Camera) -> Vec<Face> {
+        self.as_shape().sort_faces(faces, camera)
+    }
+
+    fn drawn_faces(&self, camera: &Camera) -> Vec<Face> {
+        self.as_shape().drawn_faces(camera)
+    }
+
+    fn face_style(&self, face: &Face) -> ShapeStyle {
+        self.as_shape().face_style(face)
+    }
+
+    fn has_own_style(&self, face_type: FaceType) -> bool {
+        self.as_shape().has_own_style(face_type)
+    }
+
+    fn shadow_faces(&self) -> Vec<Face> {
+        self.as_shape().shadow_faces()
+    }
+
+    fn curvature(&self) -> Option<Curvature> {
+        self.as_shape().curvature()
+    }
+}
@@ -0,0 +1,178 @@
+use super::super::frames::{CartCoord, GridCoord};
+use super::color::Color;
+use super::id::NodeId;
+use super::shape::{Bounds, Curvature, Face, FaceType, Shape, Turn};
+use super::style::ShapeStyle;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+// ============================================================================
+// Sphere
+// ============================================================================
+
+// Facettes en longitude et en latitude (sphère entière)
+const SEGMENTS: usize = 32;
+const RINGS: usize = 16;
+
+// Sphère, ou dôme (demi-sphère posée sur sa base plane). `position` est le
+// coin bas de la boîte englobante, comme pour le pavé
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sphere {
+    pub id: NodeId,
+    pub position: GridCoord,
+    // Rayon en cases
+    pub radius: f64,
+    #[serde(default)]
+    pub hemisphere: bool,
+    pub style: ShapeStyle,
+}
+
+impl Sphere {
+    pub fn new(id: NodeId, position: GridCoord, radius: f64, color: Color) -> Self {
+        Self {
+            id,
+            position,
+            radius,
+            hemisphere: false,
+            style: ShapeStyle {
+                color,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn hemisphere(id: NodeId, position: GridCoord, radius: f64, color: Color) -> Self {
+        Self {
+            hemisphere: true,
+            ..Self::new(id, position, radius, color)
+        }
+    }
+
+    pub fn with_style(mut self, style: ShapeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    // Centre de la sphère : au niveau de la base pour un dôme
+    pub fn center(&self) -> CartCoord {
+        let position: CartCoord = self.position.into();
+        let z = if self.hemisphere { 0.0 } else { self.radius };
+        position + CartCoord::new(self.radius, self.radius, z)
+    }
+
+    // Cercle de latitude `latitude` (en radians), dans le sens direct ; un
+    // pôle est réduit à un seul point
+    fn ring(&self, latitude: f64) -> Vec<CartCoord> {
+        let center = self.center();
+        let (sin, cos) = latitude.sin_cos();
+
+        if cos.abs() < 1e-9 {
+            return vec![center + CartCoord::new(0.0, 0.0, self.radius * sin)];
+        }
+
+        (0..SEGMENTS)
+            .map(|i| {
+                let (s, c) = (TAU * i as f64 / SEGMENTS as f64).sin_cos();
+                center
+                    + CartCoord::new(
+                        self.radius * cos * c,
+                        self.radius * cos * s,
+                        self.radius * sin,
+                    )
+            })
+            .collect()
+    }
+}
+
+impl Shape for Sphere {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.id = id;
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // Bandes de facettes entre latitudes successives, triangles aux pôles ;
+    // le dôme est fermé par un disque plat
+    fn faces(&self) -> Vec<Face> {
+        let (first, bottom) = if self.hemisphere {
+            (RINGS / 2, 0.0)
+        } else {
+            (0, -FRAC_PI_2)
+        };
+        let latitude = |k: usize| k as f64 * PI / RINGS as f64 - FRAC_PI_2;
+
+        let mut faces = Vec::new();
+        let mut lower = self.ring(bottom);
+
+        if self.hemisphere {
+            faces.push(Face::new(
+                FaceType::Bottom,
+                lower.iter().rev().copied().collect(),
+            ));
+        }
+
+        for k in (first + 1)..=RINGS {
+            let upper = self.ring(latitude(k));
+
+            faces.extend((0..SEGMENTS).map(|i| {
+                let j = (i + 1) % SEGMENTS;
+                let corner = |ring: &[CartCoord], i: usize| ring[i.min(ring.len() - 1)];
+
+                let mut vertices = vec![corner(&lower, i)];
+                if lower.len() > 1 {
+                    vertices.push(corner(&lower, j));
+                }
+                vertices.push(corner(&upper, j));
+                if upper.len() > 1 {
+                    vertices.push(corner(&upper, i));
+                }
+                Face::new(FaceType::Curved, vertices)
+            }));
+
+            lower = upper;
+        }
+        faces
+    }
+
+    fn bounds(&self) -> Bounds {
+        let min: CartCoord = self.position.into();
+        let diameter = 2.0 * self.radius;
+        let height = if self.hemisphere {
+            self.radius
+        } else {
+            diameter
+        };
+
+        Bounds::new(
+            min,
+            CartCoord::new(min.x + diameter, min.y + diameter, min.z + height),
+        )
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.position = self.position + offset;
+    }
+
+    // Symétrique autour de son axe : seule la position change
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        self.position = self.bounds().turned(turn, pivot).min.into();
+        self.style.turn(turn);
+    }
+
+    fn curvature(&self) -> Option<Curvature> {
+        Some(Curvature::Spherical {
+            center: self.center(),
+            radius: self.radius,
+        })
+    }
+}
@@ -0,0 +1,284 @@
+use super::super::frames::{CartCoord, GridCoord};
+use super::color::Color;
+use super::id::NodeId;
+use super::shape::{Bounds, Direction, Face, FaceType, Shape, Turn};
+use super::style::ShapeStyle;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Staircase
+// ============================================================================
+
+// Escalier plein, décrit par ses paramètres plutôt que par des pavés : il se
+// redimensionne en changeant le nombre de marches ou leur taille. Il monte
+// dans le sens de `direction`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Staircase {
+    pub id: NodeId,
+    pub position: GridCoord,
+    pub steps: u32,
+    // Hauteur et profondeur d'une marche, largeur de l'escalier, en cases
+    pub rise: f64,
+    pub run: f64,
+    pub width: f64,
+    #[serde(default)]
+    pub direction: Direction,
+    pub style: ShapeStyle,
+}
+
+impl Staircase {
+    pub fn new(
+        id: NodeId,
+        position: GridCoord,
+        steps: u32,
+        direction: Direction,
+        color: Color,
+    ) -> Self {
+        Self {
+            id,
+            position,
+            steps,
+            rise: 0.5,
+            run: 0.5,
+            width: 1.0,
+            direction,
+            style: ShapeStyle {
+                color,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn with_step_size(mut self, rise: f64, run: f64) -> Self {
+        self.rise = rise;
+        self.run = run;
+        self
+    }
+
+    pub fn with_width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn with_style(mut self, style: ShapeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    // Longueur au sol et hauteur totale
+    fn length(&self) -> f64 {
+        self.steps as f64 * self.run
+    }
+
+    fn height(&self) -> f64 {
+        self.steps as f64 * self.rise
+    }
+
+    // Taille de l'empreinte en x et en y selon le sens de la montée
+    fn footprint(&self) -> (f64, f64) {
+        match self.direction {
+            Direction::PlusX | Direction::MinusX => (self.length(), self.width),
+            Direction::PlusY | Direction::MinusY => (self.width, self.length()),
+        }
+    }
+}
+
+impl Shape for Staircase {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.id = id;
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // Dessous, dos, une marche et une contremarche par marche, et les deux
+    // flancs en profil d'escalier ; décrits dans le repère local puis orientés
+    fn faces(&self) -> Vec<Face> {
+        if self.steps == 0 {
+            return Vec::new();
+        }
+
+        let (size_x, size_y) = self.footprint();
+        let (length, width, height) = (self.length(), self.width, self.height());
+        let point = |a: f64, b: f64, z: f64| {
+            self.direction
+                .place(self.position.into(), size_x, size_y, a, b, z)
+        };
+
+        let mut faces = vec![
+            Face::new(
+                FaceType::Bottom,
+                vec![
+                    point(0.0, 0.0, 0.0),
+                    point(0.0, width, 0.0),
+                    point(length, width, 0.0),
+                    point(length, 0.0, 0.0),
+                ],
+            ),
+            Face::from_vertices(vec![
+                point(length, 0.0, 0.0),
+                point(length, width, 0.0),
+                point(length, width, height),
+                point(length, 0.0, height),
+            ]),
+        ];
+
+        for k in 0..self.steps {
+            let (a0, a1) = (k as f64 * self.run, (k + 1) as f64 * self.run);
+            let (z0, z1) = (k as f64 * self.rise, (k + 1) as f64 * self.rise);
+
+            faces.push(Face::new(
+                FaceType::Top,
+                vec![
+                    point(a0, 0.0, z1),
+                    point(a1, 0.0, z1),
+                    point(a1, width, z1),
+                    point(a0, width, z1),
+                ],
+            ));
+            faces.push(Face::from_vertices(vec![
+                point(a0, 0.0, z0),
+                point(a0, 0.0, z1),
+                point(a0, width, z1),
+                point(a0, width, z0),
+            ]));
+        }
+
+        // Profil : le sol, le dos, puis les marches en redescendant
+        let mut profile = vec![(0.0, 0.0), (length, 0.0), (length, height)];
+        for k in (0..self.steps).rev() {
+            let (a, z) = (k as f64 * self.run, k as f64 * self.rise);
+            profile.push((a, z + self.rise));
+            if k > 0 {
+                profile.push((a, z));
+            }
+        }
+
+        faces.push(Face::from_vertices(
+            profile.iter().map(|&(a, z)| point(a, 0.0, z)).collect(),
+        ));
+        faces.push(Face::from_vertices(
+            profile
+                .iter()
+                .rev()
+                .map(|&(a, z)| point(a, width, z))
+                .collect(),
+        ));
+        faces
+    }
+
+    fn bounds(&self) -> Bounds {
+        let (size_x, size_y) = self.footprint();
+        let min: CartCoord = self.position.into();
+
+        Bounds::new(
+            min,
+            CartCoord::new(min.x + size_x, min.y + size_y, min.z + self.height()),
+        )
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.position = self.position + offset;
+    }
+
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        self.position = self.bounds().turned(turn, pivot).min.into();
+        self.direction = self.direction.turned(turn);
+        self.style.turn(turn);
+    }
+
+    fn is_convex(&self) -> bool {
+        self.steps <= 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::frames::Camera;
+    use super::super::decal::Decal;
+    use super::super::document::Document;
+    use super::super::fixtures::cube;
+    use super::super::label::{Label, LabelAnchor};
+    use super::*;
+
+    // Escalier de trois marches montant vers -x : toutes les marches sont
+    // visibles dans la vue par défaut
+    fn stairs_in(document: &mut Document) -> NodeId {
+        let id = document.new_id();
+        let stairs = Staircase::new(
+            id,
+            GridCoord::new(0.0, 0.0, 0.0),
+            3,
+            Direction::MinusX,
+            Color::WHITE,
+        );
+        document.add_shape(stairs.into());
+        id
+    }
+
+    #[test]
+    fn treads_have_no_own_style() {
+        let mut document = Document::new();
+        let id = stairs_in(&mut document);
+        let stairs = document.shape(id).unwrap();
+
+        assert!(!stairs.has_own_style(FaceType::Top));
+        assert!(stairs.has_own_style(FaceType::Bottom));
+        assert!(cube((0.0, 0.0, 0.0)).has_own_style(FaceType::Top));
+    }
+
+    #[test]
+    fn labels_are_refused_on_a_tread() {
+        let mut document = Document::new();
+        let id = stairs_in(&mut document);
+        let camera = Camera::default();
+
+        // Centre de la deuxième marche, à l'écran
+        let shape = document.shape(id).unwrap().clone();
+        let tread = shape
+            .faces()
+            .into_iter()
+            .filter(|face| face.face_type == FaceType::Top)
+            .nth(1)
+            .unwrap();
+        let center = tread
+            .vertices
+            .iter()
+            .fold(CartCoord::new(0.0, 0.0, 0.0), |sum, &p| sum + p)
+            * (1.0 / tread.vertices.len() as f64);
+
+        let (hit, face) = document.face_at(camera.project(center), &camera).unwrap();
+        assert_eq!((hit, face), (id, FaceType::Top));
+        assert_eq!(LabelAnchor::on_face(&shape, face), None);
+
+        // Un texte déjà accroché au dessus de l'escalier n'est pas dessiné
+        let anchor = LabelAnchor::Face {
+            shape: id,
+            face: FaceType::Top,
+            offset: (0.0, 0.0),
+            direction: Direction::default(),
+        };
+        let label = Label::new(document.new_id(), "A".to_string(), anchor);
+        assert!(label.place(&[shape], &camera).is_none());
+    }
+
+    #[test]
+    fn decals_are_refused_on_a_tread() {
+        let mut document = Document::new();
+        let id = stairs_in(&mut document);
+        let decal = Decal::new("image.png".to_string());
+
+        assert!(!document.attach_decal(id, FaceType::Top, Some(decal.clone())));
+        assert!(document.shape(id).unwrap().style().faces.is_empty());
+        assert!(document.attach_decal(id, FaceType::Bottom, Some(decal)));
+    }
+}
@@ -0,0 +1,235 @@
+use super::color::Color;
+use super::decal::Decal;
+use super::fill::Fill;
+use super::shape::{FaceType, Turn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// ============================================================================
+// Style Properties
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShapeStyle {
+    pub color: Color,
+    pub stroke_color: Color,
+    pub stroke_width: f64,
+    pub stroke_opacity: f64,
+    pub fill_opacity: f64,
+    // Dégradé ou motif à la place de la couleur unie
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<Fill>,
+    // Surcharges par face, absentes du fichier quand il n'y en a pas. Une
+    // surcharge vaut pour toutes les faces de son type (voir
+    // `Shape::has_own_style`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub faces: BTreeMap<FaceType, FaceStyle>,
+}
+
+impl ShapeStyle {
+    pub fn new(
+        color: Color,
+        stroke_color: Color,
+        stroke_width: f64,
+        stroke_opacity: f64,
+        fill_opacity: f64,
+    ) -> Self {
+        Self {
+            color,
+            stroke_color,
+            stroke_width,
+            stroke_opacity: stroke_opacity.clamp(0.0, 1.0),
+            fill_opacity: fill_opacity.clamp(0.0, 1.0),
+            fill: None,
+            faces: BTreeMap::new(),
+        }
+    }
+
+    // Style effectif d'une face : la surcharge éventuelle appliquée au style
+    // de la forme
+    pub fn for_face(&self, face_type: FaceType) -> ShapeStyle {
+        let base = ShapeStyle {
+            faces: BTreeMap::new(),
+            ..self.clone()
+        };
+
+        match self.faces.get(&face_type) {
+            Some(face) => face.apply(base),
+            None => base,
+        }
+    }
+
+    // Surcharge d'une face, créée vide si besoin
+    pub fn face_mut(&mut self, face_type: FaceType) -> &mut FaceStyle {
+        self.faces.entry(face_type).or_default()
+    }
+
+    // Image plaquée sur une face ; une image ne concerne qu'une face et n'a
+    // donc pas d'équivalent au niveau de la forme
+    pub fn decal(&self, face_type: FaceType) -> Option<&Decal> {
+        self.faces.get(&face_type)?.decal.as_ref()
+    }
+
+    // Les surcharges suivent leurs faces quand la forme tourne ; l'image du
+    // dessus ou du dessous tourne avec elle
+    pub fn turn(&mut self, turn: Turn) {
+        self.faces = std::mem::take(&mut self.faces)
+            .into_iter()
+            .map(|(face_type, mut style)| {
+                if let (FaceType::Top | FaceType::Bottom, Some(decal)) =
+                    (face_type, style.decal.as_mut())
+                {
+                    decal.direction = decal.direction.turned(turn);
+                }
+                (face_type.turned(turn), style)
+            })
+            .collect();
+    }
+
+    // Efface la peinture d'une face en gardant le reste de sa surcharge ;
+    // la surcharge disparaît quand il n'en reste rien
+    pub fn clear_paint(&mut self, face_type: FaceType) {
+        self.edit_face(face_type, |style| {
+            style.color = None;
+            style.fill = None;
+        });
+    }
+
+    // Retire l'image d'une face, comme `clear_paint`
+    pub fn clear_decal(&mut self, face_type: FaceType) {
+        self.edit_face(face_type, |style| style.decal = None);
+    }
+
+    fn edit_face(&mut self, face_type: FaceType, edit: impl FnOnce(&mut FaceStyle)) {
+        if let Some(style) = self.faces.get_mut(&face_type) {
+            edit(style);
+            if style.is_empty() {
+                self.faces.remove(&face_type);
+            }
+        }
+    }
+
+    // Remplace la surcharge d'une face (`None` revient au style de la forme)
+    pub fn set_face(&mut self, face_type: FaceType, style: Option<FaceStyle>) {
+        match style.filter(|style| !style.is_empty()) {
+            Some(style) => self.faces.insert(face_type, style),
+            None => self.faces.remove(&face_type),
+        };
+    }
+}
+
+impl Default for ShapeStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::rgb(0x80, 0x80, 0x80),
+            stroke_color: Color::BLACK,
+            stroke_width: 1.5,
+            stroke_opacity: 1.0,
+            fill_opacity: 1.0,
+            fill: None,
+            faces: BTreeMap::new(),
+        }
+    }
+}
+
+// ============================================================================
+// Face Style
+// ============================================================================
+
+// Surcharge du style pour une seule face : les champs absents reprennent
+// ceux de la forme
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FaceStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stroke_color: Option<Color>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stroke_width: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stroke_opacity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill_opacity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<Fill>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decal: Option<Decal>,
+}
+
+impl FaceStyle {
+    pub fn with_color(color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn apply(&self, style: ShapeStyle) -> ShapeStyle {
+        ShapeStyle {
+            color: self.color.unwrap_or(style.color),
+            stroke_color: self.stroke_color.unwrap_or(style.stroke_color),
+            stroke_width: self.stroke_width.unwrap_or(style.stroke_width),
+            stroke_opacity: self
+                .stroke_opacity
+                .map_or(style.stroke_opacity, |o| o.clamp(0.0, 1.0)),
+            fill_opacity: self
+                .fill_opacity
+                .map_or(style.fill_opacity, |o| o.clamp(0.0, 1.0)),
+            fill: self.fill.clone().or(style.fill),
+            faces: style.faces,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clearing_the_paint_keeps_the_rest_of_the_face_style() {
+        let mut style = ShapeStyle::default();
+        style.face_mut(FaceType::Top).color = Some(Color::WHITE);
+        style.face_mut(FaceType::Top).stroke_width = Some(3.0);
+        style.face_mut(FaceType::Right).color = Some(Color::WHITE);
+        style.face_mut(FaceType::Right).decal = Some(Decal::new("image.png".to_string()));
+        style.face_mut(FaceType::Left).color = Some(Color::BLACK);
+        style.face_mut(FaceType::Left).fill = Some(Fill::radial(Color::WHITE, Color::BLACK));
+
+        style.clear_paint(FaceType::Top);
+        style.clear_paint(FaceType::Left);
+        style.clear_paint(FaceType::Right);
+
+        let top = FaceStyle {
+            stroke_width: Some(3.0),
+            ..Default::default()
+        };
+        let right = FaceStyle {
+            decal: Some(Decal::new("image.png".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            style.faces,
+            BTreeMap::from([(FaceType::Top, top), (FaceType::Right, right)])
+        );
+    }
+
+    #[test]
+    fn clearing_the_decal_keeps_the_paint() {
+        let mut style = ShapeStyle::default();
+        style.face_mut(FaceType::Top).color = Some(Color::WHITE);
+        style.face_mut(FaceType::Top).decal = Some(Decal::new("image.png".to_string()));
+        style.face_mut(FaceType::Left).decal = Some(Decal::new("image.png".to_string()));
+
+        style.clear_decal(FaceType::Top);
+        style.clear_decal(FaceType::Left);
+
+        assert_eq!(
+            style.faces,
+            BTreeMap::from([(FaceType::Top, FaceStyle::with_color(Color::WHITE))])
+        );
+    }
+}
@@ -0,0 +1,485 @@
+use super::super::frames::{Camera, CartCoord, GridCoord};
+use super::color::Color;
+use super::cuboid::ShapeVertices;
+use super::depth::rect_order;
+use super::id::NodeId;
+use super::shape::{Bounds, Face, FaceType, Shape, Turn};
+use super::style::ShapeStyle;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+// ============================================================================
+// Voxel Map
+// ============================================================================
+
+// Case de la grille 3D, en cases entières relatives à la position du modèle
+pub type VoxelKey = (i32, i32, i32);
+
+// Cube plein tel qu'il est écrit dans le fichier projet
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Voxel {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub color: Color,
+}
+
+// Table creuse des cubes pleins et de leur couleur. Elle est écrite comme
+// une liste : une clé composée n'a pas d'équivalent en JSON
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<Voxel>", into = "Vec<Voxel>")]
+pub struct VoxelMap {
+    cells: BTreeMap<VoxelKey, Color>,
+}
+
+impl VoxelMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, key: VoxelKey) -> Option<Color> {
+        self.cells.get(&key).copied()
+    }
+
+    pub fn contains(&self, key: VoxelKey) -> bool {
+        self.cells.contains_key(&key)
+    }
+
+    // Remplit une case ; renvoie la couleur qu'elle avait
+    pub fn insert(&mut self, key: VoxelKey, color: Color) -> Option<Color> {
+        self.cells.insert(key, color)
+    }
+
+    pub fn remove(&mut self, key: VoxelKey) -> Option<Color> {
+        self.cells.remove(&key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (VoxelKey, Color)> + '_ {
+        self.cells.iter().map(|(&key, &color)| (key, color))
+    }
+
+    // Cases extrêmes (min, max), si la table n'est pas vide
+    pub fn extent(&self) -> Option<(VoxelKey, VoxelKey)> {
+        let mut keys = self.cells.keys();
+        let first = *keys.next()?;
+
+        Some(keys.fold((first, first), |(min, max), &(x, y, z)| {
+            (
+                (min.0.min(x), min.1.min(y), min.2.min(z)),
+                (max.0.max(x), max.1.max(y), max.2.max(z)),
+            )
+        }))
+    }
+}
+
+impl From<Vec<Voxel>> for VoxelMap {
+    fn from(voxels: Vec<Voxel>) -> Self {
+        Self {
+            cells: voxels
+                .into_iter()
+                .map(|voxel| ((voxel.x, voxel.y, voxel.z), voxel.color))
+                .collect(),
+        }
+    }
+}
+
+impl From<VoxelMap> for Vec<Voxel> {
+    fn from(map: VoxelMap) -> Self {
+        map.iter()
+            .map(|((x, y, z), color)| Voxel { x, y, z, color })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Voxel Model
+// ============================================================================
+
+// Modèle en cubes d'une case, peints un à un. Il est stocké comme une seule
+// forme : seules les faces entre un cube et une case vide sont produites
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoxelModel {
+    pub id: NodeId,
+    pub position: GridCoord,
+    #[serde(default)]
+    pub voxels: VoxelMap,
+    pub style: ShapeStyle,
+}
+
+impl VoxelModel {
+    pub fn new(id: NodeId, position: GridCoord) -> Self {
+        Self {
+            id,
+            position,
+            voxels: VoxelMap::new(),
+            style: ShapeStyle::default(),
+        }
+    }
+
+    pub fn with_style(mut self, style: ShapeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    // Case qui contient un point du monde
+    pub fn key_at(&self, point: CartCoord) -> VoxelKey {
+        let origin: CartCoord = self.position.into();
+        let local = point - origin;
+        (
+            local.x.floor() as i32,
+            local.y.floor() as i32,
+            local.z.floor() as i32,
+        )
+    }
+
+    // Cube dont `face` est une face (ou un morceau de face)
+    pub fn key_behind(&self, face: &Face) -> VoxelKey {
+        self.key_at(face_center(face) - face.normal().normalized() * 0.5)
+    }
+
+    // Case vide contre `face`, où poser un nouveau cube
+    pub fn key_in_front(&self, face: &Face) -> VoxelKey {
+        self.key_at(face_center(face) + face.normal().normalized() * 0.5)
+    }
+
+    // Faces exposées d'un même plan fusionnées en rectangles (maillage
+    // glouton) : un dessus plat de 100 × 100 cubes devient une seule face.
+    // Les couleurs sont ignorées, comme pour les ombres
+    pub fn merged_faces(&self) -> Vec<Face> {
+        self.merge_faces(|_| [0; 4])
+    }
+
+    // Idem sans mélanger les couleurs : chaque rectangle ne couvre que des
+    // cubes d'une même couleur, que `face_style` retrouve
+    pub fn painted_faces(&self) -> Vec<Face> {
+        self.merge_faces(Color::to_rgb8)
+    }
+
+    // Les cubes de même `paint` sont fusionnés ensemble
+    fn merge_faces(&self, paint: impl Fn(Color) -> [u8; 4]) -> Vec<Face> {
+        let mut planes: BTreeMap<PlaneKey, BTreeSet<(i32, i32)>> = BTreeMap::new();
+        for (key, color) in self.voxels.iter() {
+            for face_type in CUBE_FACES {
+                if !self.voxels.contains(neighbor(key, face_type)) {
+                    let (depth, a, b) = plane_coords(key, face_type);
+                    planes
+                        .entry((face_type, depth, paint(color)))
+                        .or_default()
+                        .insert((a, b));
+                }
+            }
+        }
+
+        let mut faces = Vec::new();
+        for ((face_type, depth, _), mut cells) in planes {
+            while let Some(&(a, b)) = cells.first() {
+                // Rangée le long de b, étendue le long de a tant qu'elle se répète
+                let mut b_end = b;
+                while cells.contains(&(a, b_end + 1)) {
+                    b_end += 1;
+                }
+                let mut a_end = a;
+                while (b..=b_end).all(|j| cells.contains(&(a_end + 1, j))) {
+                    a_end += 1;
+                }
+                for i in a..=a_end {
+                    for j in b..=b_end {
+                        cells.remove(&(i, j));
+                    }
+                }
+
+                let min = self.cell_bounds(from_plane(face_type, depth, a, b)).min;
+                let max = self
+                    .cell_bounds(from_plane(face_type, depth, a_end, b_end))
+                    .max;
+                faces.extend(
+                    ShapeVertices::from_bounds(Bounds::new(min, max))
+                        .faces()
+                        .into_iter()
+                        .filter(|face| face.face_type == face_type),
+                );
+            }
+        }
+        faces
+    }
+
+    fn cell_bounds(&self, (x, y, z): VoxelKey) -> Bounds {
+        let min = self.position + GridCoord::new(x as f64, y as f64, z as f64);
+        Bounds::new(min.into(), (min + GridCoord::new(1.0, 1.0, 1.0)).into())
+    }
+}
+
+// Faces planes d'un cube
+const CUBE_FACES: [FaceType; 6] = [
+    FaceType::Top,
+    FaceType::Right,
+    FaceType::Left,
+    FaceType::Bottom,
+    FaceType::BackLeft,
+    FaceType::BackRight,
+];
+
+// Plan de faces fusionnées : type de face, position le long de la normale
+// et couleur commune
+type PlaneKey = (FaceType, i32, [u8; 4]);
+
+// Case dans le plan d'une face : position le long de la normale, puis les
+// deux autres coordonnées
+fn plane_coords((x, y, z): VoxelKey, face_type: FaceType) -> (i32, i32, i32) {
+    match face_type {
+        FaceType::Right | FaceType::BackLeft => (x, y, z),
+        FaceType::Left | FaceType::BackRight => (y, x, z),
+        _ => (z, x, y),
+    }
+}
+
+fn from_plane(face_type: FaceType, depth: i32, a: i32, b: i32) -> VoxelKey {
+    match face_type {
+        FaceType::Right | FaceType::BackLeft => (depth, a, b),
+        FaceType::Left | FaceType::BackRight => (a, depth, b),
+        _ => (a, b, depth),
+    }
+}
+
+// Case voisine derrière chaque face d'un cube
+fn neighbor((x, y, z): VoxelKey, face_type: FaceType) -> VoxelKey {
+    match face_type {
+        FaceType::Top => (x, y, z + 1),
+        FaceType::Bottom => (x, y, z - 1),
+        FaceType::Right => (x + 1, y, z),
+        FaceType::BackLeft => (x - 1, y, z),
+        FaceType::Left => (x, y + 1, z),
+        FaceType::BackRight => (x, y - 1, z),
+        FaceType::Curved => (x, y, z),
+    }
+}
+
+fn face_center(face: &Face) -> CartCoord {
+    let count = face.vertices.len().max(1) as f64;
+    face.vertices
+        .iter()
+        .fold(CartCoord::new(0.0, 0.0, 0.0), |sum, &p| sum + p)
+        * (1.0 / count)
+}
+
+impl Shape for VoxelModel {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.id = id;
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // Faces exposées uniquement : celles entre deux cubes pleins ne sont
+    // jamais visibles
+    fn faces(&self) -> Vec<Face> {
+        self.voxels
+            .iter()
+            .flat_map(|(key, _)| {
+                ShapeVertices::from_bounds(self.cell_bounds(key))
+                    .faces()
+                    .into_iter()
+                    .filter(move |face| !self.voxels.contains(neighbor(key, face.face_type)))
+            })
+            .collect()
+    }
+
+    fn bounds(&self) -> Bounds {
+        match self.voxels.extent() {
+            Some((min, max)) => Bounds::new(self.cell_bounds(min).min, self.cell_bounds(max).max),
+            None => {
+                let origin: CartCoord = self.position.into();
+                Bounds::new(origin, origin)
+            }
+        }
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.position = self.position + offset;
+    }
+
+    // Les centres des cubes tournent autour du pivot ; la position devient
+    // le coin bas de la nouvelle boîte pour garder des cases entières
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        let origin: CartCoord = self.bounds().turned(turn, pivot).min;
+        let centers: Vec<(CartCoord, Color)> = self
+            .voxels
+            .iter()
+            .map(|(key, color)| {
+                let center =
+                    self.cell_bounds(key).footprint_center() + CartCoord::new(0.0, 0.0, 0.5);
+                (turn.apply(center, pivot), color)
+            })
+            .collect();
+
+        self.position = origin.into();
+        self.voxels = VoxelMap::new();
+        for (center, color) in centers {
+            let local = center - origin;
+            let key = (
+                (local.x - 0.5).round() as i32,
+                (local.y - 0.5).round() as i32,
+                (local.z - 0.5).round() as i32,
+            );
+            self.voxels.insert(key, color);
+        }
+        self.style.turn(turn);
+    }
+
+    // Un seul cube est convexe. Au-delà, les cubes peuvent se cacher entre
+    // eux (assemblage en L, en escalier) : le tri ci-dessous ne coûte qu'un
+    // tri par clé, inutile de chercher les assemblages qui restent convexes
+    fn is_convex(&self) -> bool {
+        self.voxels.len() <= 1
+    }
+
+    // Des cubes d'une case sur la grille se dessinent dans l'ordre de la
+    // somme des coordonnées de la vue, sans comparer les faces deux à deux
+    fn sort_faces(&self, faces: Vec<Face>, camera: &Camera) -> Vec<Face> {
+        let mut keyed: Vec<(f64, Face)> = faces
+            .into_iter()
+            .map(|face| {
+                let center = face_center(&face) - face.normal().normalized() * 0.5;
+                let view = camera.view.apply(center);
+                (view.x + view.y + view.z, face)
+            })
+            .collect();
+
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        keyed.into_iter().map(|(_, face)| face).collect()
+    }
+
+    // Rectangles d'une couleur plutôt que faces de cube. Ils ont un ordre
+    // exact tant qu'ils ne se cachent pas en cycle ; sinon les faces des
+    // cubes, dans l'ordre ci-dessus, restent justes
+    fn drawn_faces(&self, camera: &Camera) -> Vec<Face> {
+        let faces: Vec<Face> = self
+            .painted_faces()
+            .into_iter()
+            .filter(|face| face.is_visible(camera))
+            .collect();
+
+        match rect_order(&faces, camera) {
+            Some(order) => order
+                .into_iter()
+                .map(|index| faces[index].clone())
+                .collect(),
+            None => self.sort_faces(self.visible_faces(camera), camera),
+        }
+    }
+
+    fn shadow_faces(&self) -> Vec<Face> {
+        self.merged_faces()
+    }
+
+    // Chaque face prend la couleur de son cube
+    fn face_style(&self, face: &Face) -> ShapeStyle {
+        let style = self.style.for_face(face.face_type);
+        match self.voxels.get(self.key_behind(face)) {
+            Some(color) => ShapeStyle { color, ..style },
+            None => style,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(faces: &[Face], face_type: FaceType) -> f64 {
+        faces
+            .iter()
+            .filter(|face| face.face_type == face_type)
+            .map(|face| face.normal().length() / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn flat_slab_merges_into_one_face_per_side() {
+        let mut model = VoxelModel::new(NodeId::new(), GridCoord::new(0.0, 0.0, 0.0));
+        for x in 0..10 {
+            for y in 0..10 {
+                model.voxels.insert((x, y, 0), Color::WHITE);
+            }
+        }
+
+        let merged = model.merged_faces();
+        assert_eq!(merged.len(), 6);
+        let top = merged
+            .iter()
+            .find(|face| face.face_type == FaceType::Top)
+            .unwrap();
+        assert_eq!(top.bounds().min, CartCoord::new(0.0, 0.0, 1.0));
+        assert_eq!(top.bounds().max, CartCoord::new(10.0, 10.0, 1.0));
+    }
+
+    #[test]
+    fn merged_faces_cover_the_exposed_faces() {
+        let mut model = VoxelModel::new(NodeId::new(), GridCoord::new(2.0, 0.0, 0.0));
+        for key in [
+            (0, 0, 0),
+            (1, 0, 0),
+            (2, 0, 0),
+            (0, 1, 0),
+            (0, 1, 1),
+            (2, 2, 0),
+        ] {
+            model.voxels.insert(key, Color::WHITE);
+        }
+
+        let (faces, merged) = (model.faces(), model.merged_faces());
+        assert!(merged.len() < faces.len());
+        for face_type in CUBE_FACES {
+            assert!((area(&faces, face_type) - area(&merged, face_type)).abs() < 1e-9);
+        }
+        for face in &merged {
+            assert_eq!(FaceType::from_normal(face.normal()), face.face_type);
+        }
+    }
+
+    #[test]
+    fn painted_faces_keep_colors_apart() {
+        let red = Color::new(1.0, 0.0, 0.0, 1.0);
+        let mut model = VoxelModel::new(NodeId::new(), GridCoord::new(0.0, 0.0, 0.0));
+        for x in 0..4 {
+            for y in 0..2 {
+                model
+                    .voxels
+                    .insert((x, y, 0), if x < 2 { red } else { Color::WHITE });
+            }
+        }
+
+        let tops: Vec<Face> = model
+            .painted_faces()
+            .into_iter()
+            .filter(|face| face.face_type == FaceType::Top)
+            .collect();
+        assert_eq!(tops.len(), 2);
+        for face in &tops {
+            let expected = if face.bounds().min.x < 1.0 {
+                red
+            } else {
+                Color::WHITE
+            };
+            assert_eq!(face.bounds().max.x - face.bounds().min.x, 2.0);
+            assert_eq!(model.face_style(face).color, expected);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use super::super::frames::{CartCoord, GridCoord};
+use super::color::Color;
+use super::dimensions::Dimensions;
+use super::id::NodeId;
+use super::shape::{Bounds, Direction, Face, Shape, Turn};
+use super::style::ShapeStyle;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Wedge
+// ============================================================================
+
+// Coin (rampe) : pavé coupé en diagonale, qui monte du sol jusqu'à toute sa
+// hauteur dans le sens de `direction`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Wedge {
+    pub id: NodeId,
+    pub position: GridCoord,
+    pub dimensions: Dimensions,
+    #[serde(default)]
+    pub direction: Direction,
+    pub style: ShapeStyle,
+}
+
+impl Wedge {
+    pub fn new(
+        id: NodeId,
+        position: GridCoord,
+        dimensions: Dimensions,
+        direction: Direction,
+        color: Color,
+    ) -> Self {
+        Self {
+            id,
+            position,
+            dimensions,
+            direction,
+            style: ShapeStyle {
+                color,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn with_style(mut self, style: ShapeStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Shape for Wedge {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn set_id(&mut self, id: NodeId) {
+        self.id = id;
+    }
+
+    fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+
+    fn style_mut(&mut self) -> &mut ShapeStyle {
+        &mut self.style
+    }
+
+    // Dessous, face haute, pente et deux flancs triangulaires, décrits dans
+    // le repère local puis orientés
+    fn faces(&self) -> Vec<Face> {
+        let dims = self.dimensions.with_default(1.0);
+        let (length, width) = self.direction.extent(dims.size_x, dims.size_y);
+        let height = dims.size_z;
+        let point = |a: f64, b: f64, z: f64| {
+            self.direction
+                .place(self.position.into(), dims.size_x, dims.size_y, a, b, z)
+        };
+
+        let (b00, b10) = (point(0.0, 0.0, 0.0), point(length, 0.0, 0.0));
+        let (b11, b01) = (point(length, width, 0.0), point(0.0, width, 0.0));
+        let (t10, t11) = (point(length, 0.0, height), point(length, width, height));
+
+        vec![
+            Face::from_vertices(vec![b00, b01, b11, b10]),
+            Face::from_vertices(vec![b10, b11, t11, t10]),
+            Face::from_vertices(vec![b00, t10, t11, b01]),
+            Face::from_vertices(vec![b00, b10, t10]),
+            Face::from_vertices(vec![b01, t11, b11]),
+        ]
+    }
+
+    fn bounds(&self) -> Bounds {
+        let dims = self.dimensions.with_default(1.0);
+        let min: CartCoord = self.position.into();
+
+        Bounds::new(
+            min,
+            CartCoord::new(
+                min.x + dims.size_x,
+                min.y + dims.size_y,
+                min.z + dims.size_z,
+            ),
+        )
+    }
+
+    fn translate(&mut self, offset: GridCoord) {
+        self.position = self.position + offset;
+    }
+
+    fn rotate(&mut self, turn: Turn, pivot: CartCoord) {
+        self.position = self.bounds().turned(turn, pivot).min.into();
+        self.dimensions = self.dimensions.turned();
+        self.direction = self.direction.turned(turn);
+        self.style.turn(turn);
+    }
+}
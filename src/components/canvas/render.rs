@@ -0,0 +1,904 @@
+use super::frames::{Camera, CartCoord, IsoCoord};
+use super::models::*;
+use dioxus::prelude::*;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+// Fonction pour formater les points en string SVG
+fn format_points(vertices: &[IsoCoord]) -> String {
+    vertices
+        .iter()
+        .map(|v| format!("{},{}", v.x, v.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Fonction pour formater en chemin SVG les seules arêtes marquées
+fn format_edges(vertices: &[IsoCoord], edges: &[bool]) -> String {
+    let count = vertices.len();
+
+    (0..count)
+        .filter(|&i| edges[i])
+        .map(|i| {
+            let (a, b) = (vertices[i], vertices[(i + 1) % count]);
+            format!("M{},{} L{},{}", a.x, a.y, b.x, b.y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Chaque calque visible est trié séparément puis dessiné par-dessus les
+// précédents. Les ombres et les modèles de remplissage ne dépendent que du
+// document : ils ne sont recalculés que quand il change, pas avec la vue
+#[component]
+pub fn DocumentView(document: Signal<Document>, camera: Camera) -> Element {
+    let light = document.read().light;
+    let shapes = use_memo(move || document.read().visible_shapes());
+    let casters =
+        use_memo(move || Rc::new(ShadowCasters::new(&shapes.read(), &document.read().light)));
+    let ground = use_memo(move || ground_shadows(&casters.read()));
+    let templates = use_memo(move || fill_templates(&shapes.read()));
+
+    // Un texte accroché est dessiné avec le calque de sa forme, un texte
+    // libre avec le sien
+    let labels: Vec<Label> = document
+        .read()
+        .layers
+        .iter()
+        .filter(|layer| layer.visible)
+        .flat_map(Layer::visible_labels)
+        .collect();
+    let layers: Vec<(usize, String, Vec<AnyShape>, Vec<Label>)> = document
+        .read()
+        .layers
+        .iter()
+        .enumerate()
+        .filter(|(_, layer)| layer.visible)
+        .map(|(index, layer)| {
+            let shapes = layer.visible_shapes();
+            let mut layer_labels: Vec<Label> = layer
+                .visible_labels()
+                .into_iter()
+                .filter(|label| label.attached_to().is_none())
+                .collect();
+            layer_labels.extend(
+                labels
+                    .iter()
+                    .filter(|label| {
+                        label
+                            .attached_to()
+                            .is_some_and(|id| shapes.iter().any(|shape| shape.id() == id))
+                    })
+                    .cloned(),
+            );
+            (index, layer.name.clone(), shapes, layer_labels)
+        })
+        .collect();
+
+    rsx! {
+        FillDefs { templates: templates() }
+
+        // Ombres au sol, sous toutes les formes
+        ShadowLayer { shadows: ground(), camera, opacity: light.shadow_opacity }
+
+        for (index, name, shapes, labels) in layers {
+            g {
+                key: "layer-{index}",
+                "layer": "{name}",
+                ShapeList { shapes, labels, camera, light, casters: casters() }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn ShapeList(
+    shapes: Vec<AnyShape>,
+    camera: Camera,
+    light: Light,
+    // Faces qui projettent des ombres, pour tous les calques
+    casters: Rc<ShadowCasters>,
+    #[props(default)] labels: Vec<Label>,
+    #[props(default)] mode: DepthMode,
+) -> Element {
+    let placed: Vec<PlacedLabel> = labels
+        .iter()
+        .filter_map(|label| label.place(&shapes, &camera))
+        .collect();
+
+    match render_order(&shapes, mode, &camera) {
+        // Les textes accrochés suivent leur forme dans l'ordre de rendu, et
+        // sont donc cachés par les formes placées devant
+        RenderOrder::Shapes(order) => rsx! {
+            for index in order {
+                ShapePolygon {
+                    shape: shapes[index].clone(),
+                    camera,
+                    light,
+                    shadows: received_shadows(&shapes[index], &casters),
+                }
+                for label in placed
+                    .iter()
+                    .filter(|label| label.label.attached_to() == Some(shapes[index].id()))
+                    .cloned()
+                {
+                    LabelText { placed: label, camera }
+                }
+            }
+            for label in placed.iter().filter(|label| label.label.attached_to().is_none()).cloned() {
+                LabelText { placed: label, camera }
+            }
+        },
+        RenderOrder::Fragments(fragments) => {
+            // Contours des surfaces courbes, pour ne pas tracer les arêtes
+            // entre facettes
+            let outlines: Vec<Vec<(CartCoord, CartCoord)>> = shapes
+                .iter()
+                .map(|shape| {
+                    let curved: Vec<Face> = shape
+                        .visible_faces(&camera)
+                        .into_iter()
+                        .filter(|face| face.face_type == FaceType::Curved)
+                        .collect();
+                    outline_edges(&curved)
+                })
+                .collect();
+            let fragments: Vec<Fragment> = fragments
+                .into_iter()
+                .map(|fragment| curved_edges(fragment, &outlines))
+                .collect();
+
+            rsx! {
+            for (index, fragment) in fragments.into_iter().enumerate() {
+                FragmentPolygon {
+                    id: shapes[fragment.shape].id(),
+                    index,
+                    style: shapes[fragment.shape].face_style(&fragment.face),
+                    shadows: face_shadows(&fragment.face, &casters, shapes[fragment.shape].id()),
+                    decal: fragment_decal(&shapes[fragment.shape], &fragment),
+                    fragment,
+                    camera,
+                    light,
+                }
+            }
+            for label in placed {
+                LabelText { placed: label, camera }
+            }
+            }
+        }
+    }
+}
+
+// Une forme de prévisualisation (fantôme) n'appartient pas au document
+// et ne doit pas pouvoir être sélectionnée
+#[component]
+pub fn ShapePolygon(
+    shape: AnyShape,
+    camera: Camera,
+    light: Light,
+    #[props(default)] preview: bool,
+    #[props(default)] shadows: Vec<ShadowPolygon>,
+) -> Element {
+    let selectable = if preview { "false" } else { "true" };
+
+    // Les facettes d'une surface courbe sont dessinées d'un seul tenant, sous
+    // les faces planes
+    let curved: Vec<Face> = match shape.curvature() {
+        Some(_) => shape
+            .visible_faces(&camera)
+            .into_iter()
+            .filter(|face| face.face_type == FaceType::Curved)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    // Chaque face plane, de l'arrière vers l'avant, avec son style effectif
+    // et son image éventuelle
+    let faces: Vec<(Face, ShapeStyle, Option<Decal>)> = shape
+        .drawn_faces(&camera)
+        .into_iter()
+        .map(|face| {
+            let style = shape.face_style(&face);
+            let decal = shape
+                .style()
+                .decal(face.face_type)
+                .filter(|_| shape.has_own_style(face.face_type))
+                .cloned();
+            (face, style, decal)
+        })
+        .collect();
+
+    rsx! {
+        g {
+            key: "{shape.id()}",
+            id: "{shape.id()}",
+            "inbreakable": "true",
+            "selectable": "{selectable}",
+
+            if !curved.is_empty() {
+                CurvedSurface {
+                    id: shape.id(),
+                    faces: curved,
+                    curvature: shape.curvature().unwrap_or(Curvature::Vertical),
+                    style: shape.style().for_face(FaceType::Curved),
+                    camera,
+                    light,
+                }
+            }
+
+            // Plusieurs faces peuvent avoir la même orientation (pans d'un
+            // toit) : elles sont nommées par leur position
+            for (index, (face, style, decal)) in faces.into_iter().enumerate() {
+                g {
+                    key: "{index}",
+
+                    // Un dégradé ou un motif est dessiné dessous, le polygone
+                    // ne garde alors que le contour
+                    if let Some(fill) = style.fill.clone() {
+                        FaceFill {
+                            id: format!("{}-{index}", shape.id()),
+                            face: face.clone(),
+                            fill,
+                            style: style.clone(),
+                            camera,
+                            light,
+                        }
+                    }
+                    polygon {
+                        points: format_points(&face.iso_vertices(&camera)),
+                        fill: match style.fill {
+                            Some(_) => "none".to_string(),
+                            None => light.shade(style.color, face.normal()).to_string(),
+                        },
+                        fill_opacity: "{style.fill_opacity}",
+                        stroke: "{style.stroke_color}",
+                        stroke_width: "{style.stroke_width}",
+                        stroke_opacity: "{style.stroke_opacity}",
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                    }
+
+                    // L'image passe sur le remplissage, le contour est
+                    // redessiné par-dessus
+                    if let Some(decal) = decal {
+                        FaceDecal {
+                            id: format!("{}-{index}", shape.id()),
+                            decal,
+                            face: face.clone(),
+                            clip: face.iso_vertices(&camera),
+                            camera,
+                            light,
+                        }
+                        polygon {
+                            points: format_points(&face.iso_vertices(&camera)),
+                            fill: "none",
+                            stroke: "{style.stroke_color}",
+                            stroke_width: "{style.stroke_width}",
+                            stroke_opacity: "{style.stroke_opacity}",
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                        }
+                    }
+                }
+            }
+
+            ShadowLayer { shadows, camera, opacity: light.shadow_opacity }
+        }
+    }
+}
+
+// Morceau de face issu du découpage BSP : le remplissage couvre tout le
+// fragment, le contour ne suit que les arêtes de la face d'origine
+#[component]
+pub fn FragmentPolygon(
+    id: NodeId,
+    // Position du fragment dans l'ordre de rendu, pour nommer ses motifs
+    index: usize,
+    // Style effectif de la face d'origine (`Shape::face_style`)
+    style: ShapeStyle,
+    fragment: Fragment,
+    camera: Camera,
+    light: Light,
+    #[props(default)] shadows: Vec<ShadowPolygon>,
+    // Image de la face d'origine, avec cette face entière pour la placer
+    #[props(default)] decal: Option<(Decal, Face)>,
+) -> Element {
+    let vertices = fragment.face.iso_vertices(&camera);
+
+    rsx! {
+        g {
+            "shape": "{id}",
+            "inbreakable": "true",
+            "selectable": "true",
+
+            if let Some(fill) = style.fill.clone() {
+                FaceFill {
+                    id: format!("{id}-{:?}-{index}", fragment.face.face_type),
+                    face: fragment.face.clone(),
+                    fill,
+                    style: style.clone(),
+                    camera,
+                    light,
+                }
+            } else {
+                polygon {
+                    points: format_points(&vertices),
+                    fill: "{light.shade(style.color, fragment.face.normal())}",
+                    fill_opacity: "{style.fill_opacity}",
+                    stroke: "none",
+                }
+            }
+            if let Some((decal, face)) = decal {
+                FaceDecal {
+                    id: format!("{id}-{:?}-{index}", fragment.face.face_type),
+                    decal,
+                    face,
+                    clip: vertices.clone(),
+                    camera,
+                    light,
+                }
+            }
+            ShadowLayer { shadows, camera, opacity: light.shadow_opacity }
+            path {
+                d: format_edges(&vertices, &fragment.edges),
+                fill: "none",
+                stroke: "{style.stroke_color}",
+                stroke_width: "{style.stroke_width}",
+                stroke_opacity: "{style.stroke_opacity}",
+                stroke_linecap: "round",
+                stroke_linejoin: "round",
+            }
+        }
+    }
+}
+
+// Surface courbe vue de l'extérieur : un seul polygone, le contour des
+// facettes visibles, rempli d'un dégradé qui reprend l'éclairage de la
+// surface. Seule la couleur du style est utilisée
+#[component]
+fn CurvedSurface(
+    id: NodeId,
+    faces: Vec<Face>,
+    curvature: Curvature,
+    style: ShapeStyle,
+    camera: Camera,
+    light: Light,
+) -> Element {
+    let outline: Vec<IsoCoord> = outline_polygon(&outline_edges(&faces))
+        .into_iter()
+        .map(|p| camera.project(p))
+        .collect();
+    let gradient = format!("curved-{id}");
+
+    let definition = match curvature {
+        // Flanc vertical : un arrêt par facette, à l'abscisse de son centre
+        Curvature::Vertical => {
+            let min_x = outline.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+            let max_x = outline
+                .iter()
+                .map(|p| p.x)
+                .fold(f64::NEG_INFINITY, f64::max);
+            let width = (max_x - min_x).max(f64::EPSILON);
+
+            let mut stops: Vec<(f64, Color)> = faces
+                .iter()
+                .map(|face| {
+                    let vertices = face.iso_vertices(&camera);
+                    let x = vertices.iter().map(|v| v.x).sum::<f64>() / vertices.len() as f64;
+                    ((x - min_x) / width, light.shade(style.color, face.normal()))
+                })
+                .collect();
+            stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            rsx! {
+                linearGradient {
+                    id: "{gradient}",
+                    "gradientUnits": "userSpaceOnUse",
+                    x1: "{min_x}",
+                    y1: "0",
+                    x2: "{max_x}",
+                    y2: "0",
+                    for (offset, color) in stops {
+                        stop { "offset": "{offset}", "stop-color": "{color}" }
+                    }
+                }
+            }
+        }
+        // Sphère : dégradé radial centré sur la sphère, dont le foyer est le
+        // point le plus éclairé ; le bord opposé reçoit l'éclairage de la
+        // normale visible la plus éloignée de la lumière
+        Curvature::Spherical { center, radius } => {
+            let origin = camera.project(center);
+            let focus = camera.project(center + light.direction * radius);
+            let r = outline
+                .iter()
+                .map(|p| ((p.x - origin.x).powi(2) + (p.y - origin.y).powi(2)).sqrt())
+                .fold(0.0, f64::max);
+            let stops = spherical_stops(style.color, &light, camera.view_direction());
+
+            rsx! {
+                radialGradient {
+                    id: "{gradient}",
+                    "gradientUnits": "userSpaceOnUse",
+                    cx: "{origin.x}",
+                    cy: "{origin.y}",
+                    r: "{r}",
+                    fx: "{focus.x}",
+                    fy: "{focus.y}",
+                    for (offset, color) in stops {
+                        stop { "offset": "{offset}", "stop-color": "{color}" }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        defs { {definition} }
+        polygon {
+            points: format_points(&outline),
+            fill: "url(#{gradient})",
+            fill_opacity: "{style.fill_opacity}",
+            stroke: "{style.stroke_color}",
+            stroke_width: "{style.stroke_width}",
+            stroke_opacity: "{style.stroke_opacity}",
+            stroke_linecap: "round",
+            stroke_linejoin: "round",
+        }
+    }
+}
+
+// Couleurs d'une sphère du point le plus éclairé (0) au bord opposé (1) : la
+// normale tourne de la direction de la lumière vers la normale du contour
+// apparent la plus éloignée de la lumière
+fn spherical_stops(color: Color, light: &Light, view: CartCoord) -> Vec<(f64, Color)> {
+    const STOPS: usize = 8;
+
+    let view = view.normalized();
+    let toward = light.direction;
+    let away = toward * -1.0;
+    let mut far = away - view * away.dot(view);
+    // Lumière dans l'axe de la vue : tout le contour est également sombre
+    if far.length() < 1e-9 {
+        far = view.cross(CartCoord::new(0.0, 0.0, 1.0));
+        if far.length() < 1e-9 {
+            far = CartCoord::new(1.0, 0.0, 0.0);
+        }
+    }
+    let far = far.normalized();
+
+    let angle = toward.dot(far).clamp(-1.0, 1.0).acos();
+    let side = far - toward * far.dot(toward);
+    let side = if side.length() < 1e-9 {
+        far
+    } else {
+        side.normalized()
+    };
+
+    (0..=STOPS)
+        .map(|k| {
+            let t = k as f64 / STOPS as f64;
+            let (sin, cos) = (t * angle).sin_cos();
+            (t, light.shade(color, toward * cos + side * sin))
+        })
+        .collect()
+}
+
+// Sur une surface courbe, seules les arêtes du contour sont tracées
+fn curved_edges(mut fragment: Fragment, outlines: &[Vec<(CartCoord, CartCoord)>]) -> Fragment {
+    if fragment.face.face_type != FaceType::Curved {
+        return fragment;
+    }
+
+    let outline = &outlines[fragment.shape];
+    let vertices = &fragment.face.vertices;
+    let count = vertices.len();
+
+    for i in 0..count {
+        let (p, q) = (vertices[i], vertices[(i + 1) % count]);
+        fragment.edges[i] &= outline
+            .iter()
+            .any(|&(a, b)| on_segment(p, a, b) && on_segment(q, a, b));
+    }
+    fragment
+}
+
+fn on_segment(p: CartCoord, a: CartCoord, b: CartCoord) -> bool {
+    let (ab, ap) = (b - a, p - a);
+    let length = ab.dot(ab);
+    if length < f64::EPSILON {
+        return false;
+    }
+
+    let t = ap.dot(ab) / length;
+    ab.cross(ap).length() < 1e-6 * length.sqrt() && (-1e-6..=1.0 + 1e-6).contains(&t)
+}
+
+// Texte posé dans le repère de son plan : la taille et les positions sont en
+// cases, chaque ligne est un `tspan`
+#[component]
+pub fn LabelText(placed: PlacedLabel, camera: Camera) -> Element {
+    let PlacedLabel {
+        label,
+        frame,
+        position: (x, y),
+    } = placed;
+    let style = label.style;
+
+    let anchor = match style.align {
+        TextAlign::Start => "start",
+        TextAlign::Middle => "middle",
+        TextAlign::End => "end",
+    };
+    let weight = if style.bold { "bold" } else { "normal" };
+    let font_style = if style.italic { "italic" } else { "normal" };
+
+    // Le bloc de lignes est centré verticalement sur le point d'ancrage
+    let lines: Vec<String> = label.text.lines().map(str::to_string).collect();
+    let line_height = style.font_size * 1.2;
+    let top = y - line_height * (lines.len().max(1) - 1) as f64 / 2.0;
+
+    rsx! {
+        text {
+            id: "{label.id}",
+            "selectable": "true",
+            transform: frame.svg_transform(&camera),
+            x: "{x}",
+            y: "{top}",
+            font_family: "{style.font_family}",
+            font_size: "{style.font_size}",
+            font_weight: weight,
+            font_style,
+            fill: "{style.color}",
+            "text-anchor": anchor,
+            "dominant-baseline": "central",
+
+            for (index, line) in lines.into_iter().enumerate() {
+                tspan {
+                    x: "{x}",
+                    dy: if index == 0 { "0".to_string() } else { line_height.to_string() },
+                    "{line}"
+                }
+            }
+        }
+    }
+}
+
+// Ombres reçues par un même plan : polygones opaques dans un groupe
+// translucide, pour que leurs chevauchements ne s'assombrissent pas
+#[component]
+pub fn ShadowLayer(shadows: Vec<ShadowPolygon>, camera: Camera, opacity: f64) -> Element {
+    if shadows.is_empty() {
+        return rsx! {};
+    }
+
+    let polygons: Vec<String> = shadows
+        .iter()
+        .map(|shadow| {
+            let vertices: Vec<IsoCoord> = shadow.iter().map(|&p| camera.project(p)).collect();
+            format_points(&vertices)
+        })
+        .collect();
+
+    rsx! {
+        g {
+            "shadows": "true",
+            opacity: "{opacity}",
+            fill: "black",
+            stroke: "none",
+            pointer_events: "none",
+
+            for points in polygons {
+                polygon { points }
+            }
+        }
+    }
+}
+
+// ========= Remplissages ========= //
+
+// Modèles de remplissage utilisés par les formes, un par remplissage et
+// couleur de fond, triés par identifiant. Seules les formes qui ont un
+// remplissage sont parcourues face par face
+fn fill_templates(shapes: &[AnyShape]) -> Vec<(String, Fill, Color)> {
+    let mut templates = BTreeMap::new();
+
+    for shape in shapes {
+        let style = shape.style();
+        if style.fill.is_none() && style.faces.values().all(|face| face.fill.is_none()) {
+            continue;
+        }
+
+        for face in shape.faces() {
+            let style = shape.face_style(&face);
+            if let Some(fill) = style.fill {
+                templates
+                    .entry(fill.template_id(style.color))
+                    .or_insert((fill, style.color));
+            }
+        }
+    }
+
+    templates
+        .into_iter()
+        .map(|(id, (fill, color))| (id, fill, color))
+        .collect()
+}
+
+// Les modèles ne contiennent que les couleurs et le dessin du motif ; chaque
+// face les reprend (href) avec sa propre transformation
+#[component]
+pub fn FillDefs(templates: Vec<(String, Fill, Color)>) -> Element {
+    if templates.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        defs {
+            for (id, fill, base) in templates {
+                FillTemplate { key: "{id}", id: id.clone(), fill, base }
+            }
+        }
+    }
+}
+
+#[component]
+fn FillTemplate(id: String, fill: Fill, base: Color) -> Element {
+    match fill {
+        Fill::Linear { stops, .. } => rsx! {
+            linearGradient {
+                id,
+                for stop in stops {
+                    stop { "offset": "{stop.offset}", "stop-color": "{stop.color}" }
+                }
+            }
+        },
+        Fill::Radial { stops } => rsx! {
+            radialGradient {
+                id,
+                for stop in stops {
+                    stop { "offset": "{stop.offset}", "stop-color": "{stop.color}" }
+                }
+            }
+        },
+        Fill::Pattern {
+            pattern,
+            color,
+            scale,
+            ..
+        } => {
+            let s = scale.max(0.05);
+            // Épaisseur des joints, en cases
+            let line = s * 0.04;
+            let height = if pattern == PatternKind::Bricks {
+                s / 2.0
+            } else {
+                s
+            };
+
+            rsx! {
+                pattern {
+                    id,
+                    "patternUnits": "userSpaceOnUse",
+                    width: "{s}",
+                    height: "{height}",
+
+                    rect { width: "{s}", height: "{height}", fill: "{base}" }
+
+                    match pattern {
+                        PatternKind::Stripes => rsx! {
+                            rect { width: "{s / 2.0}", height: "{s}", fill: "{color}" }
+                        },
+                        // Deux rangées décalées d'une demi-brique ; les joints du
+                        // bord sont tracés des deux côtés de la tuile
+                        PatternKind::Bricks => rsx! {
+                            path {
+                                d: "M0,0 H{s} M0,{s / 4.0} H{s} M0,{s / 2.0} H{s} M0,0 V{s / 4.0} M{s / 2.0},0 V{s / 4.0} M{s},0 V{s / 4.0} M{s / 4.0},{s / 4.0} V{s / 2.0} M{3.0 * s / 4.0},{s / 4.0} V{s / 2.0}",
+                                fill: "none",
+                                stroke: "{color}",
+                                stroke_width: "{line}",
+                            }
+                        },
+                        PatternKind::Tiles => rsx! {
+                            rect {
+                                width: "{s}",
+                                height: "{s}",
+                                fill: "none",
+                                stroke: "{color}",
+                                stroke_width: "{line * 2.0}",
+                            }
+                        },
+                        PatternKind::Dots => rsx! {
+                            circle { cx: "{s / 2.0}", cy: "{s / 2.0}", r: "{s * 0.15}", fill: "{color}" }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Face remplie par un dégradé ou un motif : instance du modèle placée dans le
+// repère de la face, puis voile noir ou blanc qui reproduit l'éclairage
+#[component]
+fn FaceFill(
+    id: String,
+    face: Face,
+    fill: Fill,
+    style: ShapeStyle,
+    camera: Camera,
+    light: Light,
+) -> Element {
+    let frame = face.frame();
+    let template = fill.template_id(style.color);
+    let instance = format!("fill-{id}");
+    let points = format_points(&face.iso_vertices(&camera));
+
+    // Transformation du repère de la face, tournée de l'angle du remplissage
+    let angle = match &fill {
+        Fill::Linear { angle, .. } | Fill::Pattern { angle, .. } => *angle,
+        Fill::Radial { .. } => 0.0,
+    };
+    let transform = format!("{} rotate({angle})", frame.svg_transform(&camera));
+
+    // Étendue de la face dans le repère tourné
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let local: Vec<(f64, f64)> = face
+        .vertices
+        .iter()
+        .map(|&p| {
+            let (u, v) = frame.local(p);
+            (u * cos + v * sin, v * cos - u * sin)
+        })
+        .collect();
+    let min_u = local.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let max_u = local.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let min_v = local.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_v = local.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    let (center_u, center_v) = ((min_u + max_u) / 2.0, (min_v + max_v) / 2.0);
+    let radius = local
+        .iter()
+        .map(|&(u, v)| ((u - center_u).powi(2) + (v - center_v).powi(2)).sqrt())
+        .fold(0.0, f64::max);
+
+    let (veil, veil_opacity) = veil(&light, face.normal());
+
+    rsx! {
+        defs {
+            match fill {
+                Fill::Linear { .. } => rsx! {
+                    linearGradient {
+                        id: "{instance}",
+                        "href": "#{template}",
+                        "gradientUnits": "userSpaceOnUse",
+                        "gradientTransform": "{transform}",
+                        x1: "{min_u}",
+                        y1: "{center_v}",
+                        x2: "{max_u}",
+                        y2: "{center_v}",
+                    }
+                },
+                Fill::Radial { .. } => rsx! {
+                    radialGradient {
+                        id: "{instance}",
+                        "href": "#{template}",
+                        "gradientUnits": "userSpaceOnUse",
+                        "gradientTransform": "{transform}",
+                        cx: "{center_u}",
+                        cy: "{center_v}",
+                        r: "{radius}",
+                    }
+                },
+                Fill::Pattern { .. } => rsx! {
+                    pattern {
+                        id: "{instance}",
+                        "href": "#{template}",
+                        "patternTransform": "{transform}",
+                    }
+                },
+            }
+        }
+        polygon {
+            points: "{points}",
+            fill: "url(#{instance})",
+            fill_opacity: "{style.fill_opacity}",
+            stroke: "none",
+        }
+        polygon {
+            points: "{points}",
+            fill: "{veil}",
+            fill_opacity: "{veil_opacity * style.fill_opacity}",
+            stroke: "none",
+            pointer_events: "none",
+        }
+    }
+}
+
+// Éclairage d'une face dessinée en SVG : voile noir à l'ombre, blanc
+// au-delà de la pleine lumière
+fn veil(light: &Light, normal: CartCoord) -> (Color, f64) {
+    let level = light.illumination(normal);
+    if level < 1.0 {
+        (Color::BLACK, 1.0 - level)
+    } else {
+        (Color::WHITE, (level - 1.0).min(1.0))
+    }
+}
+
+// ========= Images ========= //
+
+// Image de la face d'origine d'un fragment
+fn fragment_decal(shape: &AnyShape, fragment: &Fragment) -> Option<(Decal, Face)> {
+    let face_type = fragment.face.face_type;
+    let decal = shape.style().decal(face_type)?.clone();
+
+    // Sans face unique de ce type, on ne sait pas de quelle face vient le
+    // fragment : l'image n'est pas dessinée plutôt que posée sur la mauvaise
+    let mut faces = shape
+        .faces()
+        .into_iter()
+        .filter(|face| face.face_type == face_type);
+    let face = faces.next()?;
+    faces.next().is_none().then_some((decal, face))
+}
+
+// Image étirée sur l'étendue de `face` dans son repère, découpée au polygone
+// `clip` (la face ou un de ses fragments). Le voile d'éclairage est appliqué
+// par un filtre pour ne pas assombrir les parties transparentes de l'image
+#[component]
+fn FaceDecal(
+    id: String,
+    decal: Decal,
+    face: Face,
+    clip: Vec<IsoCoord>,
+    camera: Camera,
+    light: Light,
+) -> Element {
+    let frame = face.oriented_frame(decal.direction);
+    let local: Vec<(f64, f64)> = face.vertices.iter().map(|&p| frame.local(p)).collect();
+    let min_u = local.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let max_u = local.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let min_v = local.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_v = local.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+
+    // Carré unité de l'image -> étendue de la face -> écran
+    let transform = format!(
+        "{} translate({min_u} {min_v}) scale({} {})",
+        frame.svg_transform(&camera),
+        max_u - min_u,
+        max_v - min_v
+    );
+
+    // Mélange vers le noir ou le blanc, comme le voile des remplissages
+    let (veil, amount) = veil(&light, face.normal());
+    let scale = 1.0 - amount;
+    let offset = if veil == Color::WHITE { amount } else { 0.0 };
+    let matrix =
+        format!("{scale} 0 0 0 {offset} 0 {scale} 0 0 {offset} 0 0 {scale} 0 {offset} 0 0 0 1 0");
+
+    rsx! {
+        defs {
+            clipPath {
+                id: "decal-clip-{id}",
+                polygon { points: format_points(&clip) }
+            }
+            filter {
+                id: "decal-light-{id}",
+                "color-interpolation-filters": "sRGB",
+                feColorMatrix { "type": "matrix", "values": "{matrix}" }
+            }
+        }
+        g {
+            "clip-path": "url(#decal-clip-{id})",
+            "filter": "url(#decal-light-{id})",
+            opacity: "{decal.opacity}",
+            image {
+                "href": "{decal.source}",
+                width: "1",
+                height: "1",
+                "preserveAspectRatio": "none",
+                transform,
+            }
+        }
+    }
+}
@@ -1,3 +1,6 @@
+// Les modèles exposent une API plus large que ce que l'interface utilise pour l'instant
+#![allow(dead_code)]
+
 // The dioxus prelude contains a ton of common items used in dioxus apps. It's a good idea to import wherever you
 // need dioxus
 use dioxus::prelude::*;